@@ -1,3 +1,5 @@
+// the sample inputs are written out to more digits than an f32 holds
+#![allow(clippy::excessive_precision)]
 extern crate fast_math;
 
 #[macro_use]
@@ -129,6 +131,36 @@ fn bench_exp2(c: &mut Criterion) {
     bench(c, "exp2", values, &fast_math::exp2, &fast_math::exp2_raw, &f32::exp2)
 }
 
+fn bench_tan(c: &mut Criterion) {
+    let values = &[
+        0.85708036,  -2.43390621,  2.80163358,  -2.55126348,  3.18046186,
+        -2.88689427,  0.32215155,  -0.07701401,  1.22922506,  -0.4580259,
+        0.01257442,  -4.23107197,  0.89538113,  -1.65219582,  0.14632742,
+        -1.68663984,  1.88125115,  -2.16773942,  1.27461936,  -1.03091265
+    ];
+    bench(c, "tan", values, &fast_math::tan, &fast_math::tan_raw, &f32::tan)
+}
+
+fn bench_asin(c: &mut Criterion) {
+    let values = &[
+        0.85708036,  -0.43390621,  0.80163358,  -0.55126348,  0.18046186,
+        -0.88689427,  0.32215155,  -0.07701401,  0.22922506,  -0.4580259,
+        0.01257442,  -0.23107197,  0.89538113,  -0.65219582,  0.14632742,
+        -0.68663984,  0.88125115,  -0.16773942,  0.27461936,  -0.03091265
+    ];
+    bench(c, "asin", values, &fast_math::asin, &fast_math::asin_raw, &f32::asin)
+}
+
+fn bench_acos(c: &mut Criterion) {
+    let values = &[
+        0.85708036,  -0.43390621,  0.80163358,  -0.55126348,  0.18046186,
+        -0.88689427,  0.32215155,  -0.07701401,  0.22922506,  -0.4580259,
+        0.01257442,  -0.23107197,  0.89538113,  -0.65219582,  0.14632742,
+        -0.68663984,  0.88125115,  -0.16773942,  0.27461936,  -0.03091265
+    ];
+    bench(c, "acos", values, &fast_math::acos, &fast_math::acos_raw, &f32::acos)
+}
+
 fn bench_atan2(c: &mut Criterion) {
     let baseline = Fun::new(
         "baseline",
@@ -158,5 +190,33 @@ fn bench_atan2(c: &mut Criterion) {
     c.bench_functions("scalar/atan2", vec![baseline, full, std], values);
 }
 
-criterion_group!(benches, bench_log2, bench_exp, bench_exp2, bench_atan, bench_atan2);
+fn bench_sinpi(c: &mut Criterion) {
+    let baseline = Fun::new(
+        "baseline",
+        |b, i: &&[f32]| {
+            b.iter(|| for x in *i { black_box(*x); })
+        });
+
+    let full = Fun::new(
+        "full",
+        |b, i: &&[f32]| {
+            b.iter(|| for x in *i { black_box(fast_math::sinpi(*x)); } )
+        });
+    let std = Fun::new(
+        "std",
+        |b, i: &&[f32]| {
+            b.iter(|| for x in *i { black_box((x * std::f32::consts::PI).sin()); } )
+        });
+
+    let values = &[
+        0.85708036,  -2.43390621,  2.80163358,  -2.55126348,  3.18046186,
+        -2.88689427,  0.32215155,  -0.07701401,  1.22922506,  -0.4580259,
+        0.01257442,  -4.23107197,  0.89538113,  -1.65219582,  0.14632742,
+        -1.68663984,  1.88125115,  -2.16773942,  1.27461936,  -1.03091265
+    ];
+    c.bench_functions("scalar/sinpi", vec![baseline, full, std], values);
+}
+
+criterion_group!(benches, bench_log2, bench_exp, bench_exp2, bench_atan, bench_atan2,
+                 bench_tan, bench_asin, bench_acos, bench_sinpi);
 criterion_main!(benches);
@@ -12,6 +12,8 @@ fi
 
 $cargo build -v $target_param
 $cargo test -v $target_param
+$cargo test -v $target_param --features tables
+$cargo test -v $target_param --features "tables strict-raw"
 
 # for now, `cross bench` is broken https://github.com/rust-embedded/cross/issues/239
 if [ "$cargo" != "cross" ]; then
@@ -21,6 +23,8 @@ fi
 $cargo doc -v $target_param
 
 $cargo test -v --release
+# every f32, so only in release mode
+$cargo run -v --release --example exhaustive-monotone
 
 if [ ! -z "$COVERAGE" ]; then
     if [ ! -z "$TARGET" ]; then
@@ -0,0 +1,17 @@
+extern crate fast_math;
+extern crate ieee754;
+use ieee754::Ieee754;
+
+fn main() {
+    // literally test all valid floats
+    let (asin, acos) = (-1_f32).upto(1.0)
+        .map(|x| {
+            let asin = (fast_math::asin_raw(x) - x.asin()).abs();
+            let acos = (fast_math::acos_raw(x) - x.acos()).abs();
+            (asin, acos)
+        })
+        .fold((0_f32, 0_f32), |(a, a_), (b, b_)| (a.max(b), a_.max(b_)));
+
+    println!("asin_raw: absolute: {:.8}", asin);
+    println!("acos_raw: absolute: {:.8}", acos);
+}
@@ -0,0 +1,35 @@
+extern crate fast_math;
+extern crate ieee754;
+use ieee754::Ieee754;
+
+/// Check that `f(x.next()) >= f(x)` for every float in [`lo`, `hi`].
+fn check(name: &str, lo: f32, hi: f32, f: fn(f32) -> f32) {
+    let mut prev = f(lo);
+    let mut decreases = 0_u64;
+    for x in lo.next().upto(hi) {
+        let y = f(x);
+        if y < prev {
+            if decreases < 5 {
+                println!("{}: f({:e}) = {:e} < {:e}", name, x, y, prev);
+            }
+            decreases += 1;
+        }
+        prev = y;
+    }
+    println!("{}: {} decreases", name, decreases);
+    assert_eq!(decreases, 0);
+}
+
+fn main() {
+    // literally test all valid floats
+    check("exp2", f32::NEG_INFINITY, f32::INFINITY, fast_math::exp2);
+    check("exp2_raw", -126.0, 128.0f32.prev(), fast_math::exp2_raw);
+    check("exp", f32::NEG_INFINITY, f32::INFINITY, fast_math::exp);
+    check("exp_raw", -87.0, 88.0, fast_math::exp_raw);
+    check("exp2_exact_anchors", f32::NEG_INFINITY, f32::INFINITY, fast_math::exp2_exact_anchors);
+    check("exp_exact_anchors", f32::NEG_INFINITY, f32::INFINITY, fast_math::exp_exact_anchors);
+    check("log2", 0.0, f32::INFINITY, fast_math::log2);
+    check("log2_raw", f32::MIN_POSITIVE, f32::MAX, fast_math::log2_raw);
+    check("atan", f32::NEG_INFINITY, f32::INFINITY, fast_math::atan);
+    check("atan_raw", -1.0, 1.0, fast_math::atan_raw);
+}
@@ -0,0 +1,225 @@
+//! Wrapping angles in radians into a canonical range.
+//!
+//! These follow the conventions of `atan2`: `wrap_pi` leaves every
+//! result of `atan2` (including &plusmn;&pi; and &plusmn;0)
+//! unchanged, and `wrap_two_pi(atan2(y, x))` is exactly
+//! `atan2_positive(y, x)`.
+
+use core::f32::consts::{PI, TAU};
+use ieee754::Ieee754;
+
+/// 2&pi; split into three parts for Cody-Waite range reduction in
+/// f64: the first two have 19 significant bits, so that multiplying
+/// them by up to 2<sup>34</sup> turns is exact.
+const TAU_1: f64 = 6.2831878662109375;
+const TAU_2: f64 = -2.559034328442067e-6;
+const TAU_3: f64 = 2.9774189921946493e-12;
+
+/// 2<sup>36</sup>: `reduce` is accurate below this. Above it, f32s
+/// are thousands of turns apart.
+const REDUCE_LIMIT: f32 = 68719476736.0;
+
+/// The largest f32 less than `TAU`.
+const BELOW_TAU: f32 = f32::from_bits(TAU.to_bits() - 1);
+
+/// Subtract the nearest multiple of 2&pi; from `x`, for `|x| <
+/// REDUCE_LIMIT`, giving a result in [-&pi;, &pi;] (approximately).
+#[inline]
+fn reduce(x: f32) -> f64 {
+    let x = x as f64;
+    let k = (x * (0.5 * core::f64::consts::FRAC_1_PI) + 0.5f64.copy_sign(x)) as i64 as f64;
+    // the first two steps are exact
+    ((x - k * TAU_1) - k * TAU_2) - k * TAU_3
+}
+
+/// Wrap the angle `x` (in radians) into [-&pi;, &pi;].
+///
+/// Angles already in that range are returned unchanged. Others have
+/// the nearest multiple of 2&pi; subtracted, with an absolute error
+/// less than 2.4e-7 for `|x|` < 2<sup>36</sup> (about 6.9e10).
+///
+/// If `x` is NaN or infinite, or at least 2<sup>36</sup> in
+/// magnitude, where consecutive f32s are thousands of turns apart,
+/// `wrap_pi` returns NaN.
+#[inline]
+pub fn wrap_pi(x: f32) -> f32 {
+    if x.abs() <= PI {
+        x
+    } else if x.abs() < REDUCE_LIMIT {
+        // rounding to the nearest turn can leave r just outside the
+        // range
+        (reduce(x) as f32).clamp(-PI, PI)
+    } else {
+        // too large, infinite, or NaN
+        f32::NAN
+    }
+}
+
+/// Wrap the angle `x` (in radians) into [0, 2&pi;).
+///
+/// Angles already in that range are returned unchanged, except that
+/// -0 becomes 0. Others have a multiple of 2&pi; added or
+/// subtracted, with an absolute error less than 4.8e-7 for `|x|` <
+/// 2<sup>36</sup>. A tiny negative angle that would round up to 2&pi;
+/// gives the largest f32 less than 2&pi; instead, so the result is
+/// always strictly less than `TAU`.
+///
+/// If `x` is NaN or infinite, or at least 2<sup>36</sup> in
+/// magnitude, `wrap_two_pi` returns NaN.
+#[inline]
+pub fn wrap_two_pi(x: f32) -> f32 {
+    if (0.0..TAU).contains(&x) {
+        // normalise -0
+        x + 0.0
+    } else if x.abs() < REDUCE_LIMIT {
+        let r = if x.abs() <= PI {
+            // this keeps -π, from `atan2`, exactly π
+            if x < 0.0 { x + TAU } else { x + 0.0 }
+        } else {
+            // shift in f64, to round only once
+            let r = reduce(x);
+            (if r < 0.0 { r + core::f64::consts::TAU } else { r + 0.0 }) as f32
+        };
+        r.min(BELOW_TAU)
+    } else {
+        // too large, infinite, or NaN
+        f32::NAN
+    }
+}
+
+/// Compute the signed difference `a - b` between two angles (in
+/// radians), wrapped into [-&pi;, &pi;].
+///
+/// This is the rotation that takes `b` to `a` by the shortest path:
+/// positive if it is counterclockwise.
+///
+/// If either angle is NaN or infinite, or the difference is at least
+/// 2<sup>36</sup> in magnitude, `angle_diff` returns NaN.
+#[inline]
+pub fn angle_diff(a: f32, b: f32) -> f32 {
+    wrap_pi(a - b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+    use atan2;
+    use atan::atan2_positive;
+
+    /// The nearest angle to `x` in [-π, π], for `|x|` <
+    /// 2<sup>40</sup>, reducing in 2<sup>-80</sup> fixed point.
+    fn exact_wrap_pi(x: f32) -> f64 {
+        const TAU_FIXED: u128 = 7595904947272677161575987;
+        let a = (x.abs() as f64 * 2f64.powi(80)) as u128;
+        let k = (a + TAU_FIXED / 2) / TAU_FIXED;
+        let r = (a as i128 - (k * TAU_FIXED) as i128) as f64 / 2f64.powi(80);
+        if x < 0.0 { -r } else { r }
+    }
+
+    /// The distance between two angles, modulo 2π.
+    fn angle_err(a: f32, b: f64) -> f64 {
+        let tau = 2.0 * core::f64::consts::PI;
+        let d = (a as f64 - b).abs() % tau;
+        d.min(tau - d)
+    }
+
+    #[test]
+    fn wrap_pi_qc() {
+        fn prop(x: f32) -> qc::TestResult {
+            if !(x.abs() < REDUCE_LIMIT) { return qc::TestResult::discard() }
+            let w = wrap_pi(x);
+            qc::TestResult::from_bool((-PI..=PI).contains(&w) &&
+                                      angle_err(w, exact_wrap_pi(x)) < 2.4e-7)
+        }
+        qc::quickcheck(prop as fn(f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn wrap_two_pi_qc() {
+        fn prop(x: f32) -> qc::TestResult {
+            if !(x.abs() < REDUCE_LIMIT) { return qc::TestResult::discard() }
+            let w = wrap_two_pi(x);
+            qc::TestResult::from_bool((0.0..TAU).contains(&w) &&
+                                      w.is_sign_positive() &&
+                                      angle_err(w, exact_wrap_pi(x)) < 4.8e-7)
+        }
+        qc::quickcheck(prop as fn(f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn angle_diff_qc() {
+        fn prop(a: f32, b: f32) -> qc::TestResult {
+            if !(a.abs() <= PI && b.abs() <= PI) { return qc::TestResult::discard() }
+            let d = angle_diff(a, b);
+            let exact = exact_wrap_pi(a) - exact_wrap_pi(b);
+            qc::TestResult::from_bool((-PI..=PI).contains(&d) &&
+                                      angle_err(d, exact) < 4.8e-7)
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn wrap_large() {
+        // every 1001st float above π, beyond 2^31 turns, and up to
+        // the limit
+        let mut x = PI;
+        while x < REDUCE_LIMIT {
+            for &x in &[x, -x] {
+                let (w, p) = (wrap_pi(x), wrap_two_pi(x));
+                let t = exact_wrap_pi(x);
+                assert!((-PI..=PI).contains(&w) && angle_err(w, t) < 2.4e-7,
+                        "{:e}: {:e} {:e}", x, w, t);
+                assert!((0.0..TAU).contains(&p) && angle_err(p, t) < 4.8e-7,
+                        "{:e}: {:e} {:e}", x, p, t);
+            }
+            x = f32::from_bits(x.to_bits() + 1001);
+        }
+        // 3e10 rounds to 30000001024 as an f32
+        assert!((wrap_pi(3e10) as f64 + 1.6868982).abs() < 1e-7);
+        for &x in &[REDUCE_LIMIT, 1e20, f32::MAX] {
+            assert!(wrap_pi(x).is_nan() && wrap_pi(-x).is_nan(), "{:e}", x);
+            assert!(wrap_two_pi(x).is_nan() && wrap_two_pi(-x).is_nan(), "{:e}", x);
+            assert!(angle_diff(x, 0.0).is_nan(), "{:e}", x);
+        }
+    }
+
+    #[test]
+    fn consistent_with_atan2() {
+        let values = &[-2., -1., -0., 0., 1., 2., f32::INFINITY, f32::NEG_INFINITY, f32::NAN];
+        for &y in values {
+            for &x in values {
+                let a = atan2(y, x);
+                let w = wrap_pi(a);
+                let p = wrap_two_pi(a);
+                assert_eq!(a.is_nan(), w.is_nan());
+                assert_eq!(a.is_nan(), p.is_nan());
+                if !a.is_nan() {
+                    assert_eq!(w.to_bits(), a.to_bits(), "{} {}", y, x);
+                    assert_eq!(p.to_bits(), atan2_positive(y, x).to_bits(), "{} {}", y, x);
+                    assert_eq!(angle_diff(a, a), 0.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn edge_cases() {
+        for &x in &[f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert!(wrap_pi(x).is_nan());
+            assert!(wrap_two_pi(x).is_nan());
+            assert!(angle_diff(x, 0.0).is_nan());
+            assert!(angle_diff(0.0, x).is_nan());
+        }
+        assert_eq!(wrap_pi(PI), PI);
+        assert_eq!(wrap_pi(-PI), -PI);
+        assert_eq!(wrap_pi(-0.0).to_bits(), (-0.0f32).to_bits());
+        assert_eq!(wrap_two_pi(-0.0).to_bits(), 0.0f32.to_bits());
+        assert!(wrap_two_pi(TAU) < 1e-6);
+        assert_eq!(wrap_two_pi(-PI), PI);
+        assert_eq!(wrap_two_pi(-1e-30), BELOW_TAU);
+        assert!(BELOW_TAU < TAU && BELOW_TAU.next() == TAU);
+        assert!((angle_diff(3.0, -3.0) - (6.0 - TAU)).abs() < 1e-6);
+        assert!((angle_diff(-3.0, 3.0) - (TAU - 6.0)).abs() < 1e-6);
+    }
+}
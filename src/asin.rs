@@ -0,0 +1,168 @@
+use core::f32::consts::{PI, FRAC_PI_2};
+use atan::atan_raw;
+use float::flip_sign_nonnan;
+use sqrt::sqrt_raw;
+
+/// Compute a fast approximation of the inverse sine for `|x| <= 1`.
+///
+/// This will return unspecified nonsense if `x` is doesn't not
+/// satisfy those constraints. Use `asin` if correct handling is
+/// required (at the expense of some speed).
+///
+/// The maximum absolute error across all valid input is less than
+/// 0.0038.
+#[inline]
+pub fn asin_raw(x: f32) -> f32 {
+    check_raw!(x.abs() <= 1.0, "asin_raw", x, "[-1, 1]");
+    // asin(x) = atan2(x, sqrt(1 - x^2)), with the octant reduction of
+    // `atan2` inlined. Factoring 1 - x^2 avoids cancellation near
+    // |x| = 1.
+    let c = sqrt_raw((1.0 - x) * (1.0 + x));
+    if x.abs() <= c {
+        atan_raw(x / c)
+    } else {
+        flip_sign_nonnan(x, FRAC_PI_2) - atan_raw(c / x)
+    }
+}
+
+/// Compute a fast approximation of the inverse sine of `x`.
+///
+/// The maximum absolute error across all f32s is less than 0.0038.
+///
+/// If `x` is NaN, or `|x| > 1`, `asin` returns NaN.
+///
+/// See also `asin_raw` which only works on `|x| <= 1`, but is
+/// faster.
+#[inline]
+pub fn asin(x: f32) -> f32 {
+    if x.abs() <= 1.0 {
+        asin_raw(x)
+    } else {
+        // out of range, or NaN
+        f32::NAN
+    }
+}
+
+/// Compute a fast approximation of the inverse cosine for `|x| <= 1`.
+///
+/// This will return unspecified nonsense if `x` is doesn't not
+/// satisfy those constraints. Use `acos` if correct handling is
+/// required (at the expense of some speed).
+///
+/// The maximum absolute error across all valid input is less than
+/// 0.0038.
+#[inline]
+pub fn acos_raw(x: f32) -> f32 {
+    check_raw!(x.abs() <= 1.0, "acos_raw", x, "[-1, 1]");
+    // acos(x) = atan2(sqrt(1 - x^2), x), with the octant reduction of
+    // `atan2` inlined.
+    let c = sqrt_raw((1.0 - x) * (1.0 + x));
+    if c <= x.abs() {
+        let angle = atan_raw(c / x.abs());
+        if x > 0.0 { angle } else { PI - angle }
+    } else {
+        FRAC_PI_2 - atan_raw(x / c)
+    }
+}
+
+/// Compute a fast approximation of the inverse cosine of `x`.
+///
+/// The maximum absolute error across all f32s is less than 0.0038.
+///
+/// If `x` is NaN, or `|x| > 1`, `acos` returns NaN.
+///
+/// See also `acos_raw` which only works on `|x| <= 1`, but is
+/// faster.
+#[inline]
+pub fn acos(x: f32) -> f32 {
+    if x.abs() <= 1.0 {
+        acos_raw(x)
+    } else {
+        // out of range, or NaN
+        f32::NAN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+
+    /// Maximal absolute error of `atan_raw`.
+    const TOL: f32 = 0.0038;
+
+    #[test]
+    fn asin_acos_abs_err_qc() {
+        fn prop(x: f32) -> qc::TestResult {
+            if x.is_nan() || x.abs() > 1.0 { return qc::TestResult::discard() }
+            let asin_ok = (asin(x) - x.asin()).abs() < TOL;
+            let acos_ok = (acos(x) - x.acos()).abs() < TOL;
+            qc::TestResult::from_bool(asin_ok && acos_ok)
+        }
+        qc::quickcheck(prop as fn(f32) -> qc::TestResult)
+    }
+
+    const PREC: u32 = 1 << 22;
+    #[test]
+    fn asin_acos_abs_err_exhaustive() {
+        for i in 0..PREC + 1 {
+            for &sign in &[-1.0, 1.0] {
+                let x = sign * i as f32 / PREC as f32;
+                let e = asin(x);
+                let t = x.asin();
+                assert!((e - t).abs() < TOL, "{:.8}: {:.8}, {:.8}", x, e, t);
+                let e = acos(x);
+                let t = x.acos();
+                assert!((e - t).abs() < TOL, "{:.8}: {:.8}, {:.8}", x, e, t);
+            }
+        }
+    }
+
+    #[test]
+    fn asin_acos_near_one() {
+        let mut x = 1.0f32;
+        for _ in 0..1 << 16 {
+            for &x in &[x, -x] {
+                assert!((asin(x) - x.asin()).abs() < TOL, "{:.8}", x);
+                assert!((acos(x) - x.acos()).abs() < TOL, "{:.8}", x);
+            }
+            x = f32::from_bits(x.to_bits() - 1);
+        }
+    }
+
+    #[test]
+    fn asin_edge_cases() {
+        assert!(asin(f32::NAN).is_nan());
+        assert!(asin(1.0000001).is_nan());
+        assert!(asin(-1.0000001).is_nan());
+        assert!(asin(f32::INFINITY).is_nan());
+        assert!(asin(f32::NEG_INFINITY).is_nan());
+        assert_eq!(asin(0.0), 0.0);
+        assert_eq!(asin(-0.0).to_bits(), (-0.0f32).to_bits());
+        assert_eq!(asin(1.0), FRAC_PI_2);
+        assert_eq!(asin(-1.0), -FRAC_PI_2);
+    }
+
+    #[test]
+    fn acos_edge_cases() {
+        assert!(acos(f32::NAN).is_nan());
+        assert!(acos(1.0000001).is_nan());
+        assert!(acos(-1.0000001).is_nan());
+        assert!(acos(f32::INFINITY).is_nan());
+        assert!(acos(f32::NEG_INFINITY).is_nan());
+        assert_eq!(acos(0.0), FRAC_PI_2);
+        assert_eq!(acos(1.0), 0.0);
+        assert_eq!(acos(-1.0), PI);
+    }
+
+    #[test]
+    #[cfg(feature = "strict-raw")]
+    fn raw_strict_domain() {
+        ::float::assert_raw_domains(&[
+            (asin_raw, &[-1.0, 1.0], &[-2.0, 1.5, f32::NAN]),
+            (acos_raw, &[-1.0, 1.0], &[-2.0, 1.5, f32::NAN]),
+        ]);
+        assert!(asin(f32::NAN).is_nan());
+        assert!(acos(2.0).is_nan());
+    }
+}
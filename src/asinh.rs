@@ -0,0 +1,197 @@
+use core::f32;
+use core::f32::consts::{LN_2, SQRT_2};
+use ieee754::Ieee754;
+use log::{log2_1p_kernel, log2_accurate};
+use sqrt::sqrt_raw;
+
+/// Below this, asinh(x) and atanh(x) are `x` to within rounding,
+/// since the next terms of their series are x<sup>3</sup>/6 and
+/// x<sup>3</sup>/3.
+const TINY: f32 = 1.0 / 4096.0;
+
+/// Above this, x + √(x<sup>2</sup> &plusmn; 1) is 2x to within
+/// rounding, so asinh(x) and acosh(x) are ln 2x.
+const HUGE: f32 = 4096.0;
+
+/// ln(1 + u) for `u >= 0`, keeping its relative accuracy for small
+/// `u`.
+#[inline]
+fn ln_1p(u: f32) -> f32 {
+    LN_2 * if u < SQRT_2 - 1.0 { log2_1p_kernel(u) } else { log2_accurate(1.0 + u) }
+}
+
+/// ln 2x, for positive finite `x`.
+#[inline]
+fn ln_2x(x: f32) -> f32 {
+    LN_2 * (log2_accurate(x) + 1.0)
+}
+
+/// Compute a fast approximation of the inverse hyperbolic sine of
+/// `x`.
+///
+/// The maximum relative error across all f32s is less than 6e-5, and
+/// for `|x| < 2^-12` the result is exactly `x`. `asinh(±∞)` is
+/// &plusmn;&infin;.
+///
+/// If `x` is NaN, `asinh` returns NaN.
+#[inline]
+pub fn asinh(x: f32) -> f32 {
+    let a = x.abs();
+    let r = if a < TINY {
+        a
+    } else if a <= HUGE {
+        // ln(a + √(1 + a^2)), written as ln(1 + u) without
+        // cancellation in u
+        ln_1p(a + a * a / (1.0 + sqrt_raw(1.0 + a * a)))
+    } else if a < f32::INFINITY {
+        ln_2x(a)
+    } else {
+        // ∞ or NaN
+        a
+    };
+    r.copy_sign(x)
+}
+
+/// Compute a fast approximation of the inverse hyperbolic cosine of
+/// `x`, for `x >= 1`.
+///
+/// The maximum relative error across all valid input is less than
+/// 6e-5, and `acosh(1)` is exactly 0. `acosh(∞)` is &infin;.
+///
+/// If `x` is less than 1, or NaN, `acosh` returns NaN.
+#[inline]
+pub fn acosh(x: f32) -> f32 {
+    if (1.0..=HUGE).contains(&x) {
+        // ln(x + √(x^2 - 1)), written as ln(1 + u) with d = x - 1
+        // (which is exact), so that x^2 - 1 = d (2 + d) keeps its
+        // accuracy near 1
+        let d = x - 1.0;
+        ln_1p(d + sqrt_raw(d * (2.0 + d)))
+    } else if x > HUGE {
+        if x < f32::INFINITY { ln_2x(x) } else { x }
+    } else {
+        // less than 1, or NaN
+        f32::NAN
+    }
+}
+
+/// Compute a fast approximation of the inverse hyperbolic tangent of
+/// `x`, for `|x| <= 1`.
+///
+/// The maximum relative error for `|x| < 1` is less than 6e-5, and
+/// for `|x| < 2^-12` the result is exactly `x`. `atanh(±1)` is
+/// &plusmn;&infin;.
+///
+/// If `|x| > 1`, or `x` is NaN, `atanh` returns NaN.
+#[inline]
+pub fn atanh(x: f32) -> f32 {
+    let a = x.abs();
+    let r = if a < TINY {
+        a
+    } else if a < 1.0 {
+        // ln((1 + a) / (1 - a)) / 2, written as ln(1 + u) / 2 (and
+        // 1 - a is exact, for a >= 1/2)
+        0.5 * ln_1p(2.0 * a / (1.0 - a))
+    } else if a == 1.0 {
+        f32::INFINITY
+    } else {
+        // out of range, or NaN
+        f32::NAN
+    };
+    r.copy_sign(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+    use std::f32;
+
+    /// The maximum relative error of all three functions.
+    const TOL: f64 = 6e-5;
+
+    fn rel_err(e: f32, t: f64) -> f64 {
+        if t == 0.0 { e.abs() as f64 } else { (e as f64 - t).abs() / t.abs() }
+    }
+
+    #[test]
+    fn asinh_rel_err_exhaustive() {
+        // every 61st f32, of both signs
+        let mut max = 0.0f64;
+        let mut x = 0.0f32;
+        while x.is_finite() {
+            for &x in &[x, -x] {
+                let err = rel_err(asinh(x), (x as f64).asinh());
+                if err > max { max = err }
+                assert!(err < TOL, "{:e}: {:e}", x, err);
+            }
+            x = f32::from_bits(x.to_bits() + 61);
+        }
+        println!("maximum {:e}", max);
+    }
+
+    #[test]
+    fn acosh_rel_err_exhaustive() {
+        // every 61st f32 from 1
+        let mut max = 0.0f64;
+        let mut x = 1.0f32;
+        while x.is_finite() {
+            let err = rel_err(acosh(x), (x as f64).acosh());
+            if err > max { max = err }
+            assert!(err < TOL, "{:e}: {:e}", x, err);
+            x = f32::from_bits(x.to_bits() + 61);
+        }
+        println!("maximum {:e}", max);
+    }
+
+    #[test]
+    fn atanh_rel_err_exhaustive() {
+        // every 61st f32 in (-1, 1)
+        let mut max = 0.0f64;
+        let mut x = 0.0f32;
+        while x < 1.0 {
+            for &x in &[x, -x] {
+                let err = rel_err(atanh(x), (x as f64).atanh());
+                if err > max { max = err }
+                assert!(err < TOL, "{:e}: {:e}", x, err);
+            }
+            x = f32::from_bits(x.to_bits() + 61);
+        }
+        println!("maximum {:e}", max);
+    }
+
+    #[test]
+    fn odd_qc() {
+        fn prop(x: f32) -> bool {
+            asinh(-x).to_bits() == (-asinh(x)).to_bits() &&
+                (x.abs() > 1.0 || atanh(-x).to_bits() == (-atanh(x)).to_bits())
+        }
+        qc::quickcheck(prop as fn(f32) -> bool)
+    }
+
+    #[test]
+    fn edge_cases() {
+        let inf = f32::INFINITY;
+        for &x in &[0.0, -0.0, 1e-45, -1e-10, 2e-4] {
+            assert_eq!(asinh(x).to_bits(), x.to_bits());
+            assert_eq!(atanh(x).to_bits(), x.to_bits());
+        }
+        assert_eq!(asinh(inf), inf);
+        assert_eq!(asinh(-inf), -inf);
+        assert!(asinh(f32::NAN).is_nan());
+
+        assert_eq!(acosh(1.0), 0.0);
+        assert_eq!(acosh(inf), inf);
+        assert!(acosh(f32::MAX).is_finite());
+        for &x in &[1.0f32.prev(), 0.0, -1.0, -inf, f32::NAN] {
+            assert!(acosh(x).is_nan(), "{}", x);
+        }
+
+        assert_eq!(atanh(1.0), inf);
+        assert_eq!(atanh(-1.0), -inf);
+        assert!(atanh(1.0f32.prev()).is_finite());
+        for &x in &[1.0f32.next(), -2.0, inf, -inf, f32::NAN] {
+            assert!(atanh(x).is_nan(), "{}", x);
+        }
+    }
+}
@@ -2,23 +2,68 @@ use core::f32::INFINITY;
 use core::f32::consts::{PI, FRAC_PI_2, FRAC_PI_4};
 use float::{flip_sign_nonnan};
 use ieee754::Ieee754;
+use angle::wrap_two_pi;
+use hypot::{hypot, hypot_scale};
+use sinpi::sin_cos;
 
 /// Compute a fast approximation of the inverse tangent for `|x| < 1`.
 ///
 /// This will return unspecified nonsense if `x` is doesn't not
 /// satisfy those constraints. Use `atan2` if correct handling is
 /// required (at the expense of some speed).
+///
+/// This is monotone on its domain.
 #[inline]
-pub fn atan_raw(x: f32) -> f32 {
-    // Quadratic approximation recommended in
-    // http://www-labs.iro.umontreal.ca/~mignotte/IFT2425/Documents/EfficientApproximationArctgFunction.pdf.
-    const N2: f32 = 0.273;
-    (FRAC_PI_4 + N2 - N2 * x.abs()) * x
+pub const fn atan_raw(x: f32) -> f32 {
+    check_raw!(x.abs() <= 1.0, "atan_raw", x, "[-1, 1]");
+    atan_kernel(x, &RADIANS)
+}
+
+/// The angles needed to compute an arctangent in some unit, along
+/// with the coefficients of the kernel scaled to match, so that
+/// computing in that unit avoids a multiplication by &pi;.
+struct AngleUnit {
+    half: f32,
+    quarter: f32,
+    eighth: f32,
+    /// The size of a radian.
+    scale: f32,
+    n2: f32,
+}
+
+// Quadratic approximation recommended in
+// http://www-labs.iro.umontreal.ca/~mignotte/IFT2425/Documents/EfficientApproximationArctgFunction.pdf.
+const N2: f32 = 0.273;
+
+const RADIANS: AngleUnit = AngleUnit {
+    half: PI, quarter: FRAC_PI_2, eighth: FRAC_PI_4, scale: 1.0, n2: N2,
+};
+const HALF_TURNS: AngleUnit = AngleUnit {
+    half: 1.0, quarter: 0.5, eighth: 0.25, scale: 1.0 / PI, n2: N2 / PI,
+};
+const TURNS: AngleUnit = AngleUnit {
+    half: 0.5, quarter: 0.25, eighth: 0.125, scale: 0.5 / PI, n2: N2 / (2.0 * PI),
+};
+const DEGREES: AngleUnit = AngleUnit {
+    half: 180.0, quarter: 90.0, eighth: 45.0, scale: 180.0 / PI, n2: N2 * (180.0 / PI),
+};
+
+/// The arctangent of `x` in `unit`, for `|x| <= 1`.
+#[inline]
+const fn atan_kernel(x: f32, unit: &AngleUnit) -> f32 {
+    // (eighth + n2 - n2 |x|) x, but written as a small correction to
+    // the linear term: evaluating the product directly can step
+    // backwards by an ulp when the rounding of the two factors moves
+    // in opposite directions, while this is monotone (checked for
+    // every f32 by examples/exhaustive-monotone.rs).
+    let k1 = unit.eighth + unit.n2 - unit.scale;
+    unit.scale * x + x * (k1 - unit.n2 * x.abs())
 }
 
 /// Compute a fast approximation of the arctangent of `x`.
 ///
 /// The maximum absolute error across all f32s is less than 0.0038.
+/// `atan` is monotone: `atan(x) <= atan(y)` whenever `x <= y`.
 ///
 /// See also `atan_raw` which only works on `|x| <= 1`, but is faster.
 #[inline]
@@ -26,9 +71,13 @@ pub fn atan(x: f32) -> f32 {
     if x.abs() > 1.0 {
         // if x is NaN, abs(x) is NaN, so the comparison can't succeed
         debug_assert!(!x.is_nan());
-        flip_sign_nonnan(x, FRAC_PI_2) - atan_raw(1./x)
+        // the two pieces only meet to within rounding at |x| = 1, so
+        // clamp to keep `atan` monotone
+        const ATAN_1: f32 = atan_raw(1.0);
+        flip_sign_nonnan(x, (FRAC_PI_2 - atan_raw(1. / x.abs())).max(ATAN_1))
     } else {
-        atan_raw(x)
+        // NaN ends up here, and propagates
+        atan_kernel(x, &RADIANS)
     }
 }
 
@@ -37,37 +86,252 @@ pub fn atan(x: f32) -> f32 {
 /// The maximum absolute error across all f32s is less than 0.0038.
 #[inline]
 pub fn atan2(y: f32, x: f32) -> f32 {
+    atan2_in(y, x, &RADIANS)
+}
+
+/// Compute a fast approximation of the four quadrant arctangent of
+/// `y` and `x`, in half turns: `atan2(y, x) / PI`.
+///
+/// The result is in [-1, 1], and the maximum absolute error across
+/// all f32s is less than 0.0013. Angles that are exact in half turns
+/// (like 1 and 0.5) are exact, rather than carrying the rounding
+/// error of the `f32` value of &pi;.
+#[inline]
+pub fn atan2pi(y: f32, x: f32) -> f32 {
+    atan2_in(y, x, &HALF_TURNS)
+}
+
+/// Compute a fast approximation of the four quadrant arctangent of
+/// `y` and `x`, in turns: `atan2(y, x) / (2 * PI)`.
+///
+/// The result is in [-0.5, 0.5], and the maximum absolute error
+/// across all f32s is less than 0.00061. Like `atan2pi`, angles that
+/// are exact in turns are exact.
+#[inline]
+pub fn atan2_turns(y: f32, x: f32) -> f32 {
+    atan2_in(y, x, &TURNS)
+}
+
+/// Compute a fast approximation of the four quadrant arctangent of
+/// `y` and `x`, in degrees.
+///
+/// The result is in [-180, 180], with the same sign and quadrant
+/// conventions as `atan2`, and the maximum absolute error across all
+/// f32s is less than 0.22. Angles that are exact in degrees (like
+/// 180 and 90) are exact.
+#[inline]
+pub fn atan2_deg(y: f32, x: f32) -> f32 {
+    atan2_in(y, x, &DEGREES)
+}
+
+/// Compute a fast approximation of the four quadrant arctangent of
+/// `y` and `x`, in [0, 2&pi;).
+///
+/// This is exactly `angle::wrap_two_pi(atan2(y, x))`: negative
+/// angles, in the lower half plane, are shifted up by 2&pi;, and the
+/// result is always strictly less than `TAU`. When `y == -0.0`, a
+/// result of -0 (for `x` &ge; +0) becomes +0, and only a result of
+/// -&pi; (for `x` < 0, or `x` = -0) is shifted, to &pi;. The maximum absolute
+/// error across all f32s (modulo 2&pi;) is less than 0.0038.
+#[inline]
+pub fn atan2_positive(y: f32, x: f32) -> f32 {
+    wrap_two_pi(atan2(y, x))
+}
+
+#[inline(always)]
+fn atan2_in(y: f32, x: f32, unit: &AngleUnit) -> f32 {
     if y.abs() < x.abs() {
         // x is not NaN and y is finite, so there should be no NaNs
         // around
         debug_assert!(!x.is_nan() && !y.is_nan() && !(y / x).is_nan());
 
-        let bias = if x > 0.0 { 0.0 } else { PI };
-        flip_sign_nonnan(y, bias) + atan_raw(y / x)
+        let bias = if x > 0.0 { 0.0 } else { unit.half };
+        flip_sign_nonnan(y, bias) + atan_kernel(y / x, unit)
     } else if x == 0. {
         // x is non-NaN
         if y == 0. {
-            let bias = if x.is_sign_positive() { 0.0 } else { PI };
+            let bias = if x.is_sign_positive() { 0.0 } else { unit.half };
             flip_sign_nonnan(y, bias)
         } else if y.is_nan() {
             y
         } else {
-            FRAC_PI_2.copy_sign(y)
+            unit.quarter.copy_sign(y)
         }
     } else if y.abs() == INFINITY && x.abs() == INFINITY {
         // x and y are both infinite, meaning: not NaN, can't be
         // divided, and the answer is statically obvious (some
         // multiple of PI/4).
-        flip_sign_nonnan(y, FRAC_PI_2 - flip_sign_nonnan(x, FRAC_PI_4))
+        flip_sign_nonnan(y, unit.quarter - flip_sign_nonnan(x, unit.eighth))
     } else {
-        // Either one x or y is NaN (propogates through atan_raw
+        // Either one x or y is NaN (propogates through atan_kernel
         // properly), or |y| >= |x| (meaning |r| = |y / x| >= 1). Use
         // `atan(1/r) == sign(r) * pi / 2 - atan(r)`, but inline the 0
         // or PI `x` bias.
-        flip_sign_nonnan(y, FRAC_PI_2) - atan_raw(x / y)
+        flip_sign_nonnan(y, unit.quarter) - atan_kernel(x / y, unit)
+    }
+}
+
+/// Compute a fast approximation of the four quadrant arctangent of
+/// integers `y` and `x`.
+///
+/// This avoids converting `y` and `x` to `f32` before reducing to the
+/// first octant, so it handles every `i32` (including `i32::MIN`)
+/// exactly. Like `atan2`, the result is in [-&pi;, &pi;], and
+/// `atan2_i32(0, 0)` is 0.
+///
+/// The maximum absolute error across all i32s is less than 0.0038.
+///
+/// See also `fixed::atan2_binary`, which computes a more accurate
+/// binary angle without any floating point arithmetic.
+#[inline]
+pub fn atan2_i32(y: i32, x: i32) -> f32 {
+    let (ax, ay) = (x.unsigned_abs(), y.unsigned_abs());
+    let angle = if ay > ax {
+        FRAC_PI_2 - atan_raw(ax as f32 / ay as f32)
+    } else if ax == 0 {
+        // x and y are both 0
+        0.0
+    } else {
+        atan_raw(ay as f32 / ax as f32)
+    };
+    let angle = if x < 0 { PI - angle } else { angle };
+    if y < 0 { -angle } else { angle }
+}
+
+/// Compute a pseudo-angle of `y` and `x`: a cheap function that sorts
+/// in exactly the same order as the four quadrant arctangent.
+///
+/// The result is in [-4, 4], with each octant (&pi;/4 radians)
+/// covering an interval of length 1, and has the same sign and
+/// quadrant conventions as `atan2`, including for zeros and
+/// infinities.
+///
+/// This only performs a single (correctly rounded) division, and is
+/// a non-decreasing function of the exact angle, so sorting by
+/// `pseudo_angle` gives the same order as sorting by the exact
+/// `atan2`, except that angles that differ by less than the
+/// precision of `f32` may compare equal.
+///
+/// Use `pseudo_angle_to_radians` to convert the result to radians.
+#[inline]
+pub fn pseudo_angle(y: f32, x: f32) -> f32 {
+    let (ax, ay) = (x.abs(), y.abs());
+    let p = if ay <= ax {
+        let q = if ay < ax {
+            ay / ax
+        } else if ay == 0. {
+            // x and y are both zero
+            0.0
+        } else {
+            // |y| == |x|, including both being infinite
+            1.0
+        };
+        if x.is_sign_negative() { 4.0 - q } else { q }
+    } else {
+        // NaNs end up here, and propagate through the division
+        let q = ax / ay;
+        if x.is_sign_negative() { 2.0 + q } else { 2.0 - q }
+    };
+    flip_sign_nonnan(y, p)
+}
+
+/// Compute a fast approximation to the angle in radians corresponding
+/// to the pseudo-angle `p`, for -4 &le; `p` &le; 4.
+///
+/// That is, `pseudo_angle_to_radians(pseudo_angle(y, x))`
+/// approximates `atan2(y, x)`.
+///
+/// The maximum absolute error is less than 0.0038.
+#[inline]
+pub fn pseudo_angle_to_radians(p: f32) -> f32 {
+    let a = p.abs();
+    // out of range or NaN `p` give unspecified results, rather than
+    // tripping the checks of `atan_raw`
+    let angle = if a <= 1.0 {
+        atan_kernel(a, &RADIANS)
+    } else if a <= 2.0 {
+        FRAC_PI_2 - atan_kernel(2.0 - a, &RADIANS)
+    } else if a <= 3.0 {
+        FRAC_PI_2 + atan_kernel(a - 2.0, &RADIANS)
+    } else {
+        PI - atan_kernel(4.0 - a, &RADIANS)
+    };
+    flip_sign_nonnan(p, angle)
+}
+
+/// Compute a fast approximation to the pseudo-angle (see
+/// `pseudo_angle`) corresponding to the angle `x` in radians, for
+/// -&pi; &le; `x` &le; &pi;.
+///
+/// The maximum absolute error is less than 0.0004, and the result is
+/// exact at every multiple of &pi;/4.
+#[inline]
+pub fn radians_to_pseudo_angle(x: f32) -> f32 {
+    // tan(u pi / 4) on [0, 1], constrained to be exact at 0 and 1.
+    #[inline(always)]
+    #[allow(clippy::excessive_precision)]
+    fn tan_octant(u: f32) -> f32 {
+        const A: f32 = -0.2122585233;
+        const B: f32 = -0.06717214266;
+        let u2 = u * u;
+        u + u * (1.0 - u2) * (A + B * u2)
+    }
+
+    let s = x.abs() * (4.0 / PI);
+    let p = if s <= 1.0 {
+        tan_octant(s)
+    } else if s <= 2.0 {
+        2.0 - tan_octant(2.0 - s)
+    } else if s <= 3.0 {
+        2.0 + tan_octant(s - 2.0)
+    } else {
+        4.0 - tan_octant(4.0 - s)
+    };
+    flip_sign_nonnan(x, p)
+}
+
+/// Compute fast approximations of the polar coordinates `(r, theta)`
+/// of the point (`x`, `y`), together.
+///
+/// This gives exactly `(hypot(x, y), atan2(y, x))`, but both use the
+/// ratio of the smaller to the larger of `|x|` and `|y|`, so this
+/// only performs one division rather than two. The maximum relative
+/// error of `r` is less than 3e-7, and the maximum absolute error of
+/// `theta` is less than 0.0038.
+#[inline]
+pub fn to_polar(x: f32, y: f32) -> (f32, f32) {
+    let (ax, ay) = (x.abs(), y.abs());
+    // the first two branches match those of `atan2_in`; ties, zeros,
+    // and NaNs fall through to the separate functions
+    if ay < ax {
+        let t = y / x;
+        let bias = if x > 0.0 { 0.0 } else { PI };
+        (ax * hypot_scale(t.abs()), flip_sign_nonnan(y, bias) + atan_kernel(t, &RADIANS))
+    } else if ax < ay {
+        let t = x / y;
+        (ay * hypot_scale(t.abs()), flip_sign_nonnan(y, FRAC_PI_2) - atan_kernel(t, &RADIANS))
+    } else {
+        (hypot(x, y), atan2(y, x))
     }
 }
 
+/// Compute fast approximations of the cartesian coordinates `(x, y)`
+/// of the point with polar coordinates `r` and `theta`, that is,
+/// `(r * cos(theta), r * sin(theta))`.
+///
+/// The sine and cosine share a single range reduction, the same as
+/// that of `tan`. For `|theta| <= 8192`, the maximum absolute error
+/// of each coordinate is less than 2e-6 `|r|`; beyond that, the
+/// results have magnitude at most `|r|` but unspecified accuracy.
+///
+/// If `theta` is NaN or infinite, or `r` is NaN, `from_polar` returns
+/// NaNs.
+#[inline]
+pub fn from_polar(r: f32, theta: f32) -> (f32, f32) {
+    let (s, c) = sin_cos(theta);
+    (r * c, r * s)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,6 +382,48 @@ mod tests {
         assert_eq!(atan(f::INFINITY), PI / 2.);
     }
 
+    #[test]
+    fn atan_monotone() {
+        // every float around the switch at |x| = 1, and, for
+        // `atan_raw`, around the ends of its domain and the change of
+        // sign of |x| at 0
+        let full = &[atan as fn(f32) -> f32];
+        let raw = &[atan_raw as fn(f32) -> f32];
+        for &(lo, hi, fs) in &[(-2.0, -0.5, &full[..]), (0.5, 2.0, &full[..]),
+                               (-1.0, -0.5, &raw[..]), (0.5, 1.0, &raw[..]),
+                               (-f32::MIN_POSITIVE, f32::MIN_POSITIVE, &raw[..])] {
+            for &f in fs {
+                let mut prev = f(lo);
+                for x in lo.next().upto(hi) {
+                    let e = f(x);
+                    assert!(e >= prev, "{:e}: {:e} < {:e}", x, e, prev);
+                    prev = e;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn atan_raw_const() {
+        const N: usize = 1 << 12;
+        const fn input(i: usize) -> f32 {
+            -1.0 + 2.0 * (i as f32 / N as f32)
+        }
+        static TABLE: [f32; N + 1] = {
+            let mut table = [0.0; N + 1];
+            let mut i = 0;
+            while i <= N {
+                table[i] = atan_raw(input(i));
+                i += 1;
+            }
+            table
+        };
+        for (i, &e) in TABLE.iter().enumerate() {
+            let x = input(i);
+            assert_eq!(e.to_bits(), atan_raw(x).to_bits(), "{}", x);
+        }
+    }
+
     #[test]
     fn atan_denormals() {
         fn prop(x: u8, y: u16) -> bool {
@@ -168,4 +474,286 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn atan2pi_turns_abs_err_qc() {
+        fn prop(y: f32, x: f32) -> qc::TestResult {
+            let t = (y as f64).atan2(x as f64) / core::f64::consts::PI;
+            let pi = (atan2pi(y, x) as f64 - t).abs();
+            let turns = (atan2_turns(y, x) as f64 - t / 2.0).abs();
+            qc::TestResult::from_bool(pi < 0.0013 && turns < 0.00061)
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn atan2pi_turns_edge_cases() {
+        let values = &[-2., -1., -0., 0., 1., 2., f32::INFINITY, f32::NEG_INFINITY, f32::NAN];
+        for &y in values {
+            for &x in values {
+                let e = atan2(y, x);
+                let pi = atan2pi(y, x);
+                let turns = atan2_turns(y, x);
+                assert_eq!(e.is_nan(), pi.is_nan());
+                assert_eq!(e.is_nan(), turns.is_nan());
+                if !e.is_nan() {
+                    assert!((-1.0..=1.0).contains(&pi));
+                    assert!((-0.5..=0.5).contains(&turns));
+                    assert_eq!(pi.is_sign_negative(), e.is_sign_negative());
+                    assert!((pi * PI - e).abs() < 1e-6);
+                    assert!((turns * 2.0 * PI - e).abs() < 1e-6);
+                }
+            }
+        }
+        // exact in the unit, with no rounding error from PI
+        assert_eq!(atan2pi(0.0, -1.0), 1.0);
+        assert_eq!(atan2pi(-0.0, -1.0), -1.0);
+        assert_eq!(atan2pi(1.0, 0.0), 0.5);
+        assert_eq!(atan2_turns(0.0, -1.0), 0.5);
+        assert_eq!(atan2_turns(-1.0, 0.0), -0.25);
+    }
+
+    #[test]
+    fn atan2_deg_abs_err_qc() {
+        fn prop(y: f32, x: f32) -> bool {
+            let t = (y as f64).atan2(x as f64).to_degrees();
+            (atan2_deg(y, x) as f64 - t).abs() < 0.22
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> bool)
+    }
+
+    #[test]
+    fn atan2_deg_positive_edge_cases() {
+        let values = &[-2., -1., -0., 0., 1., 2., f32::INFINITY, f32::NEG_INFINITY, f32::NAN];
+        for &y in values {
+            for &x in values {
+                let e = atan2(y, x);
+                let deg = atan2_deg(y, x);
+                let pos = atan2_positive(y, x);
+                assert_eq!(e.is_nan(), deg.is_nan());
+                assert_eq!(e.is_nan(), pos.is_nan());
+                if !e.is_nan() {
+                    assert_eq!(deg.is_sign_negative(), e.is_sign_negative());
+                    assert!((deg - e.to_degrees()).abs() < 1e-4);
+                    assert!((0.0..2.0 * PI).contains(&pos) && pos.is_sign_positive());
+                    let shift = if e < 0.0 { 2.0 * PI } else { 0.0 };
+                    assert!((pos - (e + shift)).abs() < 1e-6);
+                }
+            }
+        }
+        assert_eq!(atan2_deg(0.0, -1.0), 180.0);
+        assert_eq!(atan2_deg(-0.0, -1.0), -180.0);
+        assert_eq!(atan2_deg(-1.0, 0.0), -90.0);
+        assert_eq!(atan2_positive(-0.0, 1.0).to_bits(), 0.0f32.to_bits());
+        assert_eq!(atan2_positive(-0.0, -1.0), PI);
+        assert_eq!(atan2_positive(-0.0, -0.0), PI);
+        assert_eq!(atan2_positive(-0.0, 0.0).to_bits(), 0.0f32.to_bits());
+        assert!(atan2_positive(-1e-30, 1.0) < 2.0 * PI);
+    }
+
+    #[test]
+    fn atan2_positive_abs_err_qc() {
+        fn prop(y: f32, x: f32) -> bool {
+            let tau = 2.0 * core::f64::consts::PI;
+            let t = (y as f64).atan2(x as f64);
+            let t = if t < 0.0 { t + tau } else { t };
+            let d = (atan2_positive(y, x) as f64 - t).abs();
+            d.min(tau - d) < 0.0038
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> bool)
+    }
+
+    #[test]
+    fn atan2_i32_abs_err_qc() {
+        fn prop(y: i32, x: i32) -> bool {
+            let e = atan2_i32(y, x);
+            let t = (y as f64).atan2(x as f64) as f32;
+            (e - t).abs() < TOL
+        }
+        qc::quickcheck(prop as fn(i32, i32) -> bool)
+    }
+
+    #[test]
+    fn atan2_i32_edge_cases() {
+        let values = &[i32::MIN, i32::MIN + 1, -2, -1, 0, 1, 2, i32::MAX - 1, i32::MAX];
+        for &x in values {
+            for &y in values {
+                let e = atan2_i32(y, x);
+                let t = (y as f64).atan2(x as f64) as f32;
+                assert!((e - t).abs() < TOL, "{}, {}: {}, {}", y, x, e, t);
+            }
+        }
+        assert_eq!(atan2_i32(0, 0), 0.0);
+        assert_eq!(atan2_i32(0, 1), 0.0);
+        assert_eq!(atan2_i32(0, i32::MIN), PI);
+        assert_eq!(atan2_i32(1, 0), FRAC_PI_2);
+        assert_eq!(atan2_i32(i32::MIN, 0), -FRAC_PI_2);
+    }
+
+    /// The exact pseudo-angle, computed from the exact angle.
+    fn exact_pseudo_angle(y: f32, x: f32) -> f64 {
+        let angle = (y as f64).atan2(x as f64);
+        let s = angle.abs() / (std::f64::consts::PI / 4.0);
+        let p = if s <= 1.0 {
+            (s * std::f64::consts::PI / 4.0).tan()
+        } else if s <= 2.0 {
+            2.0 - ((2.0 - s) * std::f64::consts::PI / 4.0).tan()
+        } else if s <= 3.0 {
+            2.0 + ((s - 2.0) * std::f64::consts::PI / 4.0).tan()
+        } else {
+            4.0 - ((4.0 - s) * std::f64::consts::PI / 4.0).tan()
+        };
+        p.copysign(angle)
+    }
+
+    #[test]
+    fn pseudo_angle_qc() {
+        fn prop(y: f32, x: f32) -> bool {
+            let e = pseudo_angle(y, x);
+            let t = exact_pseudo_angle(y, x);
+            if t.is_nan() {
+                e.is_nan()
+            } else {
+                (e as f64 - t).abs() < 1e-6
+            }
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> bool)
+    }
+
+    #[test]
+    fn pseudo_angle_monotone() {
+        const N: u32 = 1 << 16;
+        for &scale in &[1e-40, 1e-3, 1.0, 1e30] {
+            let mut points = (0..N + 1).map(|i| {
+                let angle = -std::f64::consts::PI + 2.0 * std::f64::consts::PI * i as f64 / N as f64;
+                let (y, x) = ((scale * angle.sin()) as f32, (scale * angle.cos()) as f32);
+                // the rounding to f32 perturbs the angle, so use the
+                // exact angle of the rounded point
+                ((y as f64).atan2(x as f64), pseudo_angle(y, x), y, x)
+            }).collect::<std::vec::Vec<_>>();
+            points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+            for w in points.windows(2) {
+                assert!(w[0].1 <= w[1].1, "{:?} {:?}", w[0], w[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn pseudo_angle_edge_cases() {
+        let values = &[-2., -1., -0., 0., 1., 2., f32::INFINITY, f32::NEG_INFINITY, f32::NAN];
+        for &x in values {
+            for &y in values {
+                let e = pseudo_angle(y, x);
+                let t = exact_pseudo_angle(y, x);
+                assert_eq!(e.is_nan(), t.is_nan());
+                if !t.is_nan() {
+                    assert!((e as f64 - t).abs() < 1e-12, "{}, {}: {}, {}", y, x, e, t);
+                    assert_eq!(e.is_sign_negative(), t.is_sign_negative(), "{}, {}", y, x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pseudo_angle_to_radians_abs_err() {
+        for i in 0..PREC + 1 {
+            let p = -4.0 + 8.0 * i as f32 / PREC as f32;
+            let e = pseudo_angle_to_radians(p);
+            let t = exact_pseudo_angle_inverse(p);
+            assert!((e as f64 - t).abs() < TOL as f64, "{}: {}, {}", p, e, t);
+        }
+        assert!(pseudo_angle_to_radians(f32::NAN).is_nan());
+    }
+
+    /// The exact angle corresponding to a pseudo-angle.
+    fn exact_pseudo_angle_inverse(p: f32) -> f64 {
+        let a = p.abs() as f64;
+        let angle = if a <= 1.0 {
+            a.atan()
+        } else if a <= 2.0 {
+            std::f64::consts::FRAC_PI_2 - (2.0 - a).atan()
+        } else if a <= 3.0 {
+            std::f64::consts::FRAC_PI_2 + (a - 2.0).atan()
+        } else {
+            std::f64::consts::PI - (4.0 - a).atan()
+        };
+        angle.copysign(p as f64)
+    }
+
+    #[test]
+    fn radians_to_pseudo_angle_abs_err() {
+        for i in 0..PREC + 1 {
+            let x = -PI + 2.0 * PI * i as f32 / PREC as f32;
+            let e = radians_to_pseudo_angle(x);
+            let t = exact_pseudo_angle(x.sin(), x.cos());
+            // -4 and 4 are the same direction
+            let diff = (e as f64 - t).abs();
+            assert!(diff.min(8.0 - diff) < 0.0004, "{}: {}, {}", x, e, t);
+        }
+        for i in -4..5 {
+            assert_eq!(radians_to_pseudo_angle(i as f32 * FRAC_PI_4), i as f32);
+        }
+        assert!(radians_to_pseudo_angle(f32::NAN).is_nan());
+    }
+
+    #[test]
+    #[cfg(feature = "strict-raw")]
+    fn raw_strict_domain() {
+        ::float::assert_raw_domains(&[(atan_raw, &[-1.0, 1.0], &[-1.5, 1.5, f32::NAN])]);
+        // the full functions never trip the check, even for NaN
+        assert!(atan(f32::NAN).is_nan());
+        assert!(pseudo_angle_to_radians(f32::NAN).is_nan());
+        pseudo_angle_to_radians(5.0);
+    }
+
+    #[test]
+    fn to_polar_matches_qc() {
+        fn prop(x: f32, y: f32) -> bool {
+            let (r, theta) = to_polar(x, y);
+            r.to_bits() == hypot(x, y).to_bits() && theta.to_bits() == atan2(y, x).to_bits()
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> bool)
+    }
+
+    #[test]
+    fn to_polar_special() {
+        let values = [0.0, -0.0, 1.0, -1.0, 1e-45, -3e38, f32::INFINITY, f32::NEG_INFINITY, f32::NAN];
+        for &x in &values {
+            for &y in &values {
+                let (r, theta) = to_polar(x, y);
+                let (r2, theta2) = (hypot(x, y), atan2(y, x));
+                assert!(r.to_bits() == r2.to_bits() || (r.is_nan() && r2.is_nan()), "{} {}", x, y);
+                assert!(theta.to_bits() == theta2.to_bits() || (theta.is_nan() && theta2.is_nan()),
+                        "{} {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn from_polar_err_qc() {
+        fn prop(r: f32, theta: f32) -> qc::TestResult {
+            if !r.is_finite() || theta.is_nan() || theta.abs() > 8192.0 { return qc::TestResult::discard() }
+            let (x, y) = from_polar(r, theta);
+            let (r64, t64) = (r as f64, theta as f64);
+            let tol = 2e-6 * r64.abs();
+            qc::TestResult::from_bool((x as f64 - r64 * t64.cos()).abs() <= tol &&
+                                      (y as f64 - r64 * t64.sin()).abs() <= tol)
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn polar_round_trip() {
+        let mut theta = -PI;
+        while theta <= PI {
+            let (x, y) = from_polar(2.0, theta);
+            let (r, t) = to_polar(x, y);
+            assert!((r - 2.0).abs() < 1e-5, "{}: {}", theta, r);
+            assert!((t - theta).abs() < TOL || (t - theta).abs() > 2.0 * PI - TOL, "{}: {}", theta, t);
+            theta += 0.001;
+        }
+        let (x, y) = from_polar(1.0, f32::NAN);
+        assert!(x.is_nan() && y.is_nan());
+    }
 }
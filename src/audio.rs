@@ -0,0 +1,209 @@
+//! Conversions between gains and decibels, and between MIDI note
+//! numbers and frequencies.
+//!
+//! Errors are given in the units that matter for audio: decibels for
+//! gains, and cents (hundredths of a semitone) for pitches. Silence,
+//! a gain of 0, is -&infin; dB, just as `log2(0.0)` is -&infin;, and
+//! converts back to a gain of exactly 0.
+//!
+//! ```rust
+//! use fast_math::audio;
+//!
+//! assert_eq!(audio::midi_to_hz(81.0), 880.0);
+//! assert_eq!(audio::hz_to_midi(440.0), 69.0);
+//! assert!((audio::db_to_gain(-6.0) - 0.5012).abs() < 0.002);
+//! assert_eq!(audio::gain_to_db(0.0), std::f32::NEG_INFINITY);
+//! ```
+
+use core::f32;
+use exp::exp2_exact_anchors;
+use log::log2_precise;
+
+/// log<sub>2</sub> 10 / 20, from decibels to powers of two of gain.
+const DB_TO_LOG2: f32 = 0.166096404744368117393515971474469508;
+/// 20 log<sub>10</sub> 2, from powers of two of gain to decibels.
+const LOG2_TO_DB: f32 = 6.02059991327962390427477789448986053;
+/// The frequency of MIDI note 69, A<sub>4</sub>.
+const A4_HZ: f32 = 440.0;
+const A4_NOTE: f32 = 69.0;
+
+/// Compute a fast approximation of the gain (amplitude ratio) of `x`
+/// decibels, 10<sup>`x`/20</sup>.
+///
+/// The maximum error is less than 0.025 dB (a relative error of
+/// 0.0028) for `x` from about -758 to 770, where the result is
+/// normal, and `db_to_gain(0.0)` is exactly 1. Below that the result
+/// is 0, and above it, &infin;. In particular, `db_to_gain(-∞)` is 0,
+/// inverting `gain_to_db(0.0)`.
+///
+/// If `x` is NaN, `db_to_gain` returns NaN.
+#[inline]
+pub fn db_to_gain(x: f32) -> f32 {
+    exp2_exact_anchors(x * DB_TO_LOG2)
+}
+
+/// Compute a fast approximation of the gain (amplitude ratio) `x` in
+/// decibels, 20 log<sub>10</sub> `x`.
+///
+/// The maximum error is less than 1e-4 dB, and `gain_to_db(1.0)`
+/// is exactly 0.
+///
+/// `gain_to_db(0.0)` is -&infin; (silence), and `gain_to_db(∞)` is
+/// &infin;. Like `log2`, if `x` is negative, or NaN, `gain_to_db`
+/// returns NaN, so the magnitude of a signed sample should be passed
+/// instead.
+#[inline]
+pub fn gain_to_db(x: f32) -> f32 {
+    LOG2_TO_DB * log2_precise(x)
+}
+
+/// Compute a fast approximation of the frequency in hertz of MIDI note
+/// `n`, 440 &times; 2<sup>(`n` - 69)/12</sup>, in twelve-tone equal
+/// temperament with A<sub>4</sub> = 440 Hz.
+///
+/// Fractional notes give the frequencies in between. The maximum
+/// error is less than 4.9 cents (a relative error of 0.0028) for
+/// notes from about -1440 to 1490 (far beyond hearing in both
+/// directions), and every A (`n` = 69 + 12k) is exact:
+/// `midi_to_hz(57.0)` is exactly 220. Above that range the result is
+/// &infin;, and `midi_to_hz(-∞)` is 0.
+///
+/// If `n` is NaN, `midi_to_hz` returns NaN.
+#[inline]
+pub fn midi_to_hz(n: f32) -> f32 {
+    A4_HZ * exp2_exact_anchors((n - A4_NOTE) / 12.0)
+}
+
+/// Compute a fast approximation of the MIDI note of the frequency `f`
+/// in hertz, 69 + 12 log<sub>2</sub>(`f` / 440), the inverse of
+/// `midi_to_hz`.
+///
+/// The result is fractional between the notes of the scale. The
+/// maximum error is less than 0.015 cents while `f` / 440 is normal
+/// (`f` above about 5.2e-36 Hz), and every A (`f` = 440 &times;
+/// 2<sup>k</sup>) is exact: `hz_to_midi(440.0)` is exactly 69.
+///
+/// `hz_to_midi(0.0)` is -&infin;, and `hz_to_midi(∞)` is &infin;. If
+/// `f` is negative, or NaN, `hz_to_midi` returns NaN.
+#[inline]
+pub fn hz_to_midi(f: f32) -> f32 {
+    A4_NOTE + 12.0 * log2_precise(f / A4_HZ)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+    use std::f32;
+
+    /// The ratio of `e` to `t` in decibels.
+    fn db_err(e: f32, t: f64) -> f64 {
+        20.0 * (e as f64 / t).log10()
+    }
+
+    /// The ratio of `e` to `t` in cents.
+    fn cents_err(e: f32, t: f64) -> f64 {
+        1200.0 * (e as f64 / t).log2()
+    }
+
+    #[test]
+    fn db_to_gain_err() {
+        let mut max = 0.0f64;
+        let mut x = -758.0f32;
+        while x < 770.0 {
+            let err = db_err(db_to_gain(x), 10f64.powf(x as f64 / 20.0)).abs();
+            if err > max { max = err }
+            assert!(err < 0.025, "{}: {:e}", x, err);
+            x += 0.0037;
+        }
+        println!("maximum {:e} dB", max);
+    }
+
+    #[test]
+    fn gain_to_db_err_exhaustive() {
+        // every 61st positive f32
+        let mut max = 0.0f64;
+        let mut x = 1e-45f32;
+        while x.is_finite() {
+            let err = (gain_to_db(x) as f64 - 20.0 * (x as f64).log10()).abs();
+            if err > max { max = err }
+            assert!(err < 1e-4, "{:e}: {:e}", x, err);
+            x = f32::from_bits(x.to_bits() + 61);
+        }
+        println!("maximum {:e} dB", max);
+    }
+
+    #[test]
+    fn midi_to_hz_err() {
+        let mut max = 0.0f64;
+        let mut n = -1440.0f32;
+        while n < 1490.0 {
+            let t = 440.0 * 2f64.powf((n as f64 - 69.0) / 12.0);
+            let err = cents_err(midi_to_hz(n), t).abs();
+            if err > max { max = err }
+            assert!(err < 4.9, "{}: {:e}", n, err);
+            n += 0.0041;
+        }
+        println!("maximum {:e} cents", max);
+    }
+
+    #[test]
+    fn hz_to_midi_err_exhaustive() {
+        // every 61st f32 from 5.2e-36
+        let mut max = 0.0f64;
+        let mut f = 5.2e-36f32;
+        while f.is_finite() {
+            let t = 69.0 + 12.0 * (f as f64 / 440.0).log2();
+            let err = 100.0 * (hz_to_midi(f) as f64 - t).abs();
+            if err > max { max = err }
+            assert!(err < 0.015, "{:e}: {:e}", f, err);
+            f = f32::from_bits(f.to_bits() + 61);
+        }
+        println!("maximum {:e} cents", max);
+    }
+
+    #[test]
+    fn round_trip_qc() {
+        fn prop(n: f32) -> qc::TestResult {
+            if n.is_nan() || n.abs() >= 1000.0 { return qc::TestResult::discard() }
+            // 4.9 cents out, and less than 0.05 back
+            let back = hz_to_midi(midi_to_hz(n));
+            qc::TestResult::from_bool((back - n).abs() < 0.0495 + n.abs() * f32::EPSILON)
+        }
+        qc::quickcheck(prop as fn(f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn exact_at_a() {
+        for k in -10..10 {
+            let n = 69.0 + 12.0 * k as f32;
+            let f = 440.0 * (k as f32).exp2();
+            assert_eq!(midi_to_hz(n), f, "{}", k);
+            assert_eq!(hz_to_midi(f), n, "{}", k);
+        }
+        assert_eq!(db_to_gain(0.0), 1.0);
+        assert_eq!(gain_to_db(1.0), 0.0);
+    }
+
+    #[test]
+    fn edge_cases() {
+        let inf = f32::INFINITY;
+        assert_eq!(gain_to_db(0.0), -inf);
+        assert_eq!(db_to_gain(gain_to_db(0.0)), 0.0);
+        assert_eq!(gain_to_db(inf), inf);
+        assert_eq!(db_to_gain(inf), inf);
+        assert_eq!(db_to_gain(800.0), inf);
+        assert_eq!(db_to_gain(-800.0), 0.0);
+        assert!(gain_to_db(-1.0).is_nan());
+        assert!(gain_to_db(f32::NAN).is_nan());
+        assert!(db_to_gain(f32::NAN).is_nan());
+
+        assert_eq!(hz_to_midi(0.0), -inf);
+        assert_eq!(hz_to_midi(inf), inf);
+        assert_eq!(midi_to_hz(-inf), 0.0);
+        assert_eq!(midi_to_hz(inf), inf);
+        assert!(hz_to_midi(-440.0).is_nan());
+        assert!(hz_to_midi(f32::NAN).is_nan());
+        assert!(midi_to_hz(f32::NAN).is_nan());
+    }
+}
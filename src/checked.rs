@@ -0,0 +1,495 @@
+//! Versions of the functions that report invalid input as an error,
+//! instead of returning NaN or unspecified nonsense.
+//!
+//! Each function here returns `Ok` with exactly the result of the
+//! function of the same name at the top level of the crate whenever
+//! that is meaningful, and otherwise a `DomainError` describing why
+//! the input was rejected. The checks for the `_raw` functions
+//! follow their documented domains, narrowed for `exp_raw` and
+//! `exp2_raw` to where the result is normal and finite, so an `Ok`
+//! result always has the documented accuracy.
+//!
+//! ```rust
+//! use fast_math::checked::{self, DomainError};
+//!
+//! assert_eq!(checked::log2(8.0), Ok(3.0));
+//! assert_eq!(checked::log2(-1.0), Err(DomainError::Negative));
+//! assert_eq!(checked::exp_raw(200.0), Err(DomainError::OutOfRawRange));
+//! ```
+
+use core::{f32, fmt};
+use core::f32::consts::LOG2_E;
+use ieee754::Ieee754;
+
+/// The reason an input was rejected by a function in the `checked`
+/// module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainError {
+    /// The input was NaN.
+    NaN,
+    /// The input was negative, for a function only defined on
+    /// non-negative numbers.
+    Negative,
+    /// The input was infinite, for a function that has no meaningful
+    /// value there.
+    Infinite,
+    /// The input was finite, but outside the domain of the function,
+    /// such as `asin(2.0)`.
+    OutOfRange,
+    /// The input is valid for the full function, but outside the
+    /// documented domain of the faster `_raw` version.
+    OutOfRawRange,
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            DomainError::NaN => "input is NaN",
+            DomainError::Negative => "input is negative",
+            DomainError::Infinite => "input is infinite",
+            DomainError::OutOfRange => "input is outside the domain of the function",
+            DomainError::OutOfRawRange => "input is outside the domain of the raw function",
+        })
+    }
+}
+
+#[inline]
+fn not_nan(x: f32) -> Result<f32, DomainError> {
+    if x.is_nan() { Err(DomainError::NaN) } else { Ok(x) }
+}
+
+#[inline]
+fn finite(x: f32) -> Result<f32, DomainError> {
+    let x = not_nan(x)?;
+    if x.is_infinite() { Err(DomainError::Infinite) } else { Ok(x) }
+}
+
+/// Check that `|x| <= limit`, for a finite `limit`.
+#[inline]
+fn within(x: f32, limit: f32, err: DomainError) -> Result<f32, DomainError> {
+    let x = finite(x)?;
+    if x.abs() <= limit { Ok(x) } else { Err(err) }
+}
+
+/// Compute `f(x)` for `lo <= x < hi`, rejecting results which round
+/// up to infinity just below `hi`.
+#[inline]
+fn normal_exp(x: f32, lo: f32, hi: f32, f: fn(f32) -> f32) -> Result<f32, DomainError> {
+    let e = if lo <= x && x < hi { f(x) } else { f32::INFINITY };
+    if e.is_finite() { Ok(e) } else { Err(DomainError::OutOfRawRange) }
+}
+
+#[inline]
+fn non_negative(x: f32) -> Result<f32, DomainError> {
+    let x = not_nan(x)?;
+    if x < 0.0 { Err(DomainError::Negative) } else { Ok(x) }
+}
+
+/// Compute `log2(x)`, for non-negative `x`.
+///
+/// `x` may be zero or infinite, giving an infinite result, but -0 is
+/// rejected as negative, like in `log2`.
+#[inline]
+pub fn log2(x: f32) -> Result<f32, DomainError> {
+    let x = not_nan(x)?;
+    if x.is_sign_negative() { Err(DomainError::Negative) } else { Ok(::log2(x)) }
+}
+
+/// Compute `log2_raw(x)`, for positive, finite, non-denormal `x`.
+#[inline]
+pub fn log2_raw(x: f32) -> Result<f32, DomainError> {
+    let x = not_nan(x)?;
+    if x.is_sign_negative() {
+        Err(DomainError::Negative)
+    } else if x.is_infinite() {
+        Err(DomainError::Infinite)
+    } else if x < f32::MIN_POSITIVE {
+        Err(DomainError::OutOfRawRange)
+    } else {
+        Ok(::log2_raw(x))
+    }
+}
+
+/// Compute `log2_1p(x)`, for `x >= -1`.
+#[inline]
+pub fn log2_1p(x: f32) -> Result<f32, DomainError> {
+    let x = not_nan(x)?;
+    if x >= -1.0 { Ok(::log2_1p(x)) } else { Err(DomainError::OutOfRange) }
+}
+
+/// Compute `log1p(x)`, for `x >= -1`.
+#[inline]
+pub fn log1p(x: f32) -> Result<f32, DomainError> {
+    let x = not_nan(x)?;
+    if x >= -1.0 { Ok(::log1p(x)) } else { Err(DomainError::OutOfRange) }
+}
+
+/// Compute `exp(x)`, for any `x` except NaN.
+#[inline]
+pub fn exp(x: f32) -> Result<f32, DomainError> {
+    not_nan(x).map(::exp)
+}
+
+/// Compute `exp_raw(x)`, for -126 ln 2 &le; `x` < 128 ln 2, where
+/// the result is normal and finite.
+///
+/// This is narrower than the domain of `exp_raw`, which also allows
+/// subnormal results with less accuracy, and inputs just below 128 ln
+/// 2 which round up to &infin; are rejected too.
+#[inline]
+pub fn exp_raw(x: f32) -> Result<f32, DomainError> {
+    let x = finite(x)?;
+    normal_exp(x, -126.0 / LOG2_E, 128.0 / LOG2_E, ::exp_raw)
+}
+
+/// Compute `exp2(x)`, for any `x` except NaN.
+#[inline]
+pub fn exp2(x: f32) -> Result<f32, DomainError> {
+    not_nan(x).map(::exp2)
+}
+
+/// Compute `exp2_raw(x)`, for -126 &le; `x` < 128, where the result
+/// is normal and finite.
+///
+/// Like `exp_raw`, this is narrower than the domain of `exp2_raw`.
+#[inline]
+pub fn exp2_raw(x: f32) -> Result<f32, DomainError> {
+    let x = finite(x)?;
+    normal_exp(x, -126.0, 128.0, ::exp2_raw)
+}
+
+/// Compute `atan(x)`, for any `x` except NaN.
+#[inline]
+pub fn atan(x: f32) -> Result<f32, DomainError> {
+    not_nan(x).map(::atan)
+}
+
+/// Compute `atan_raw(x)`, for `|x| <= 1`.
+#[inline]
+pub fn atan_raw(x: f32) -> Result<f32, DomainError> {
+    within(x, 1.0, DomainError::OutOfRawRange).map(::atan_raw)
+}
+
+/// Compute `atan2(y, x)`, for any `y` and `x` except NaN.
+#[inline]
+pub fn atan2(y: f32, x: f32) -> Result<f32, DomainError> {
+    Ok(::atan2(not_nan(y)?, not_nan(x)?))
+}
+
+/// Compute `asin(x)`, for `|x| <= 1`.
+#[inline]
+pub fn asin(x: f32) -> Result<f32, DomainError> {
+    within(x, 1.0, DomainError::OutOfRange).map(::asin)
+}
+
+/// Compute `acos(x)`, for `|x| <= 1`.
+#[inline]
+pub fn acos(x: f32) -> Result<f32, DomainError> {
+    within(x, 1.0, DomainError::OutOfRange).map(::acos)
+}
+
+/// Compute `asinh(x)`, for any `x` except NaN.
+#[inline]
+pub fn asinh(x: f32) -> Result<f32, DomainError> {
+    not_nan(x).map(::asinh)
+}
+
+/// Compute `acosh(x)`, for `x >= 1`.
+#[inline]
+pub fn acosh(x: f32) -> Result<f32, DomainError> {
+    let x = not_nan(x)?;
+    if x < 1.0 { Err(DomainError::OutOfRange) } else { Ok(::acosh(x)) }
+}
+
+/// Compute `atanh(x)`, for `|x| <= 1`.
+#[inline]
+pub fn atanh(x: f32) -> Result<f32, DomainError> {
+    within(x, 1.0, DomainError::OutOfRange).map(::atanh)
+}
+
+/// Compute `tan(x)`, for finite `x`.
+#[inline]
+pub fn tan(x: f32) -> Result<f32, DomainError> {
+    finite(x).map(::tan)
+}
+
+/// Compute `tan_raw(x)`, for `|x| <= 8192`.
+#[inline]
+pub fn tan_raw(x: f32) -> Result<f32, DomainError> {
+    within(x, 8192.0, DomainError::OutOfRawRange).map(::tan_raw)
+}
+
+/// Compute `sinpi(x)`, for finite `x`.
+#[inline]
+pub fn sinpi(x: f32) -> Result<f32, DomainError> {
+    finite(x).map(::sinpi)
+}
+
+/// Compute `cospi(x)`, for finite `x`.
+#[inline]
+pub fn cospi(x: f32) -> Result<f32, DomainError> {
+    finite(x).map(::cospi)
+}
+
+/// Compute `erf(x)`, for any `x` except NaN.
+#[inline]
+pub fn erf(x: f32) -> Result<f32, DomainError> {
+    not_nan(x).map(::erf)
+}
+
+/// Compute `erf_raw(x)`, for `|x| <= 9`.
+#[inline]
+pub fn erf_raw(x: f32) -> Result<f32, DomainError> {
+    within(x, 9.0, DomainError::OutOfRawRange).map(::erf_raw)
+}
+
+/// Compute `erfc(x)`, for any `x` except NaN.
+#[inline]
+pub fn erfc(x: f32) -> Result<f32, DomainError> {
+    not_nan(x).map(::erfc)
+}
+
+/// Compute `erfc_raw(x)`, for `|x| <= 9`.
+#[inline]
+pub fn erfc_raw(x: f32) -> Result<f32, DomainError> {
+    within(x, 9.0, DomainError::OutOfRawRange).map(::erfc_raw)
+}
+
+/// Compute `erf_inv(x)`, for `|x| <= 1`.
+#[inline]
+pub fn erf_inv(x: f32) -> Result<f32, DomainError> {
+    within(x, 1.0, DomainError::OutOfRange).map(::erf_inv)
+}
+
+/// Compute `erf_inv_raw(x)`, for `|x| < 1`.
+#[inline]
+pub fn erf_inv_raw(x: f32) -> Result<f32, DomainError> {
+    within(x, 1.0f32.prev(), DomainError::OutOfRawRange).map(::erf_inv_raw)
+}
+
+/// Compute `lgamma(x)`, for non-negative `x`, including ±0.
+#[inline]
+pub fn lgamma(x: f32) -> Result<f32, DomainError> {
+    non_negative(x).map(::lgamma)
+}
+
+/// Compute `gamma(x)`, for non-negative `x`, including ±0.
+#[inline]
+pub fn gamma(x: f32) -> Result<f32, DomainError> {
+    non_negative(x).map(::gamma)
+}
+
+/// Compute `digamma(x)`, for non-negative `x`, including ±0.
+#[inline]
+pub fn digamma(x: f32) -> Result<f32, DomainError> {
+    non_negative(x).map(::digamma)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::DomainError::*;
+    use quickcheck as qc;
+    use ieee754::Ieee754;
+    use std::f32;
+    use std::string::ToString;
+
+    type Pair = (fn(f32) -> Result<f32, DomainError>, fn(f32) -> f32);
+
+    const PAIRS: &[Pair] = &[
+        (log2, ::log2), (log2_raw, ::log2_raw), (log2_1p, ::log2_1p), (log1p, ::log1p),
+        (exp, ::exp), (exp_raw, ::exp_raw), (exp2, ::exp2), (exp2_raw, ::exp2_raw),
+        (atan, ::atan), (atan_raw, ::atan_raw), (asin, ::asin), (acos, ::acos),
+        (tan, ::tan), (tan_raw, ::tan_raw), (sinpi, ::sinpi), (cospi, ::cospi),
+        (erf, ::erf), (erf_raw, ::erf_raw), (erfc, ::erfc), (erfc_raw, ::erfc_raw),
+        (erf_inv, ::erf_inv), (erf_inv_raw, ::erf_inv_raw),
+        (lgamma, ::lgamma), (gamma, ::gamma), (digamma, ::digamma),
+        (asinh, ::asinh), (acosh, ::acosh), (atanh, ::atanh),
+    ];
+
+    /// A `_raw` function with its exact value, given the input and the
+    /// approximation, and its documented maximum error, relative or
+    /// absolute.
+    type Bound = (fn(f32) -> Result<f32, DomainError>, fn(f32, f32) -> f64, f64, bool);
+
+    const RAW: &[Bound] = &[
+        (log2_raw, |x, _| (x as f64).log2(), 0.009, false),
+        (exp_raw, |x, _| (x as f64).exp(), 0.011, true),
+        (exp2_raw, |x, _| (x as f64).exp2(), 0.011, true),
+        (atan_raw, |x, _| (x as f64).atan(), 0.0038, false),
+        (tan_raw, |x, _| (x as f64).tan(), 1e-4, true),
+        (erf_raw, |x, _| ::reference::exact_erf(x as f64), 1.2e-5, false),
+        (erfc_raw, |x, _| ::reference::exact_erfc(x as f64), 1.2e-5, false),
+        (erf_inv_raw, ::reference::exact_erf_inv, 3e-5, true),
+    ];
+
+    #[test]
+    fn ok_matches_unchecked_qc() {
+        fn prop(x: f32) -> bool {
+            PAIRS.iter().all(|&(checked, f)| {
+                match checked(x) {
+                    Ok(e) => e.to_bits() == f(x).to_bits(),
+                    Err(_) => true,
+                }
+            })
+        }
+        qc::quickcheck(prop as fn(f32) -> bool)
+    }
+
+    #[test]
+    fn ok_is_not_nan_qc() {
+        fn prop(x: f32) -> bool {
+            PAIRS.iter().all(|&(checked, _)| checked(x).map_or(true, |e| !e.is_nan()))
+        }
+        qc::quickcheck(prop as fn(f32) -> bool)
+    }
+
+    #[test]
+    fn ok_exhaustive() {
+        // every 997th float, of both signs
+        let mut x = 0.0f32;
+        while x.is_finite() {
+            for &x in &[x, -x] {
+                for &(checked, f) in PAIRS {
+                    if let Ok(e) = checked(x) {
+                        assert!(!e.is_nan(), "{:e}", x);
+                        assert_eq!(e.to_bits(), f(x).to_bits(), "{:e}", x);
+                    }
+                }
+                for &(checked, exact, tol, relative) in RAW {
+                    if let Ok(e) = checked(x) {
+                        assert!(e.is_finite(), "{:e}: {:e}", x, e);
+                        let t = exact(x, e);
+                        // subnormal results are only as precise as their spacing
+                        let tiny = 1.4e-45;
+                        let tol = if relative { tol * t.abs() + tiny } else { tol };
+                        assert!((e as f64 - t).abs() <= tol, "{:e}: {:e}, {:e}", x, e, t);
+                    }
+                }
+            }
+            x = f32::from_bits(x.to_bits() + 997);
+        }
+    }
+
+    #[test]
+    fn nan() {
+        for &(checked, _) in PAIRS {
+            assert_eq!(checked(f32::NAN), Err(NaN));
+        }
+        assert_eq!(atan2(f32::NAN, 1.0), Err(NaN));
+        assert_eq!(atan2(1.0, f32::NAN), Err(NaN));
+    }
+
+    #[test]
+    fn log2_errors() {
+        assert_eq!(log2(-1.0), Err(Negative));
+        assert_eq!(log2(-0.0), Err(Negative));
+        assert_eq!(log2(f32::NEG_INFINITY), Err(Negative));
+        assert_eq!(log2(0.0), Ok(f32::NEG_INFINITY));
+        assert_eq!(log2(f32::INFINITY), Ok(f32::INFINITY));
+        assert_eq!(log2(1e-40), Ok(::log2(1e-40)));
+
+        assert_eq!(log2_raw(-1.0), Err(Negative));
+        assert_eq!(log2_raw(f32::INFINITY), Err(Infinite));
+        assert_eq!(log2_raw(0.0), Err(OutOfRawRange));
+        assert_eq!(log2_raw(1e-40), Err(OutOfRawRange));
+        assert_eq!(log2_raw(f32::MIN_POSITIVE), Ok(-126.0));
+        assert_eq!(log2_raw(f32::MAX), Ok(::log2_raw(f32::MAX)));
+
+        for &f in &[log2_1p as fn(f32) -> Result<f32, DomainError>, log1p] {
+            assert_eq!(f(-1.5), Err(OutOfRange));
+            assert_eq!(f(f32::NEG_INFINITY), Err(OutOfRange));
+            assert_eq!(f(-1.0), Ok(f32::NEG_INFINITY));
+            assert_eq!(f(f32::INFINITY), Ok(f32::INFINITY));
+        }
+    }
+
+    #[test]
+    fn exp_errors() {
+        assert_eq!(exp(f32::INFINITY), Ok(f32::INFINITY));
+        assert_eq!(exp2(f32::NEG_INFINITY), Ok(0.0));
+        assert_eq!(exp_raw(88.7), Ok(::exp_raw(88.7)));
+        assert_eq!(exp_raw(-87.3), Ok(::exp_raw(-87.3)));
+        assert_eq!(exp_raw(88.8), Err(OutOfRawRange));
+        assert_eq!(exp_raw(-87.4), Err(OutOfRawRange));
+        assert_eq!(exp_raw(f32::NEG_INFINITY), Err(Infinite));
+        assert_eq!(exp2_raw(128.0f32.prev()), Ok(::exp2_raw(128.0f32.prev())));
+        assert_eq!(exp2_raw(-126.0), Ok(::exp2_raw(-126.0)));
+        assert_eq!(exp2_raw(128.0), Err(OutOfRawRange));
+        assert_eq!(exp2_raw(-126.5), Err(OutOfRawRange));
+        assert_eq!(exp2_raw(f32::INFINITY), Err(Infinite));
+    }
+
+    #[test]
+    fn trig_errors() {
+        assert_eq!(atan(f32::INFINITY), Ok(::atan(f32::INFINITY)));
+        assert_eq!(atan_raw(1.0), Ok(::atan_raw(1.0)));
+        assert_eq!(atan_raw(-1.5), Err(OutOfRawRange));
+        assert_eq!(atan_raw(f32::INFINITY), Err(Infinite));
+        assert_eq!(atan2(f32::INFINITY, 0.0), Ok(::atan2(f32::INFINITY, 0.0)));
+
+        for &f in &[asin as fn(f32) -> Result<f32, DomainError>, acos] {
+            assert!(f(1.0).is_ok());
+            assert!(f(-1.0).is_ok());
+            assert_eq!(f(1.5), Err(OutOfRange));
+            assert_eq!(f(f32::NEG_INFINITY), Err(Infinite));
+        }
+
+        for &f in &[tan as fn(f32) -> Result<f32, DomainError>, sinpi, cospi] {
+            assert!(f(f32::MAX).is_ok());
+            assert_eq!(f(f32::INFINITY), Err(Infinite));
+            assert_eq!(f(f32::NEG_INFINITY), Err(Infinite));
+        }
+        assert_eq!(asinh(f32::NEG_INFINITY), Ok(f32::NEG_INFINITY));
+        assert_eq!(acosh(1.0), Ok(0.0));
+        assert_eq!(acosh(f32::INFINITY), Ok(f32::INFINITY));
+        assert_eq!(acosh(0.5), Err(OutOfRange));
+        assert_eq!(acosh(f32::NEG_INFINITY), Err(OutOfRange));
+        assert_eq!(atanh(-1.0), Ok(f32::NEG_INFINITY));
+        assert_eq!(atanh(1.5), Err(OutOfRange));
+        assert_eq!(atanh(f32::INFINITY), Err(Infinite));
+
+        assert!(tan_raw(8192.0).is_ok());
+        assert_eq!(tan_raw(-8193.0), Err(OutOfRawRange));
+    }
+
+    #[test]
+    fn erf_errors() {
+        assert_eq!(erf(f32::INFINITY), Ok(1.0));
+        assert_eq!(erfc(f32::NEG_INFINITY), Ok(2.0));
+        for &f in &[erf_raw as fn(f32) -> Result<f32, DomainError>, erfc_raw] {
+            assert!(f(9.0).is_ok());
+            assert!(f(-9.0).is_ok());
+            assert_eq!(f(9.5), Err(OutOfRawRange));
+            assert_eq!(f(f32::INFINITY), Err(Infinite));
+        }
+
+        assert_eq!(erf_inv(1.0), Ok(f32::INFINITY));
+        assert_eq!(erf_inv(-1.0), Ok(f32::NEG_INFINITY));
+        assert_eq!(erf_inv(1.5), Err(OutOfRange));
+        assert_eq!(erf_inv(f32::INFINITY), Err(Infinite));
+        assert_eq!(erf_inv_raw(1.0f32.prev()), Ok(::erf_inv_raw(1.0f32.prev())));
+        assert_eq!(erf_inv_raw(-1.0), Err(OutOfRawRange));
+    }
+
+    #[test]
+    fn gamma_errors() {
+        for &f in &[lgamma as fn(f32) -> Result<f32, DomainError>, gamma, digamma] {
+            assert!(f(0.0).is_ok());
+            assert!(f(-0.0).is_ok());
+            assert!(f(f32::INFINITY).is_ok());
+            assert_eq!(f(-1.0), Err(Negative));
+            assert_eq!(f(-0.5), Err(Negative));
+            assert_eq!(f(f32::NEG_INFINITY), Err(Negative));
+        }
+        assert_eq!(gamma(-0.0), Ok(f32::NEG_INFINITY));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(NaN.to_string(), "input is NaN");
+        assert_eq!(Negative.to_string(), "input is negative");
+        assert_eq!(OutOfRawRange.to_string(),
+                   "input is outside the domain of the raw function");
+    }
+}
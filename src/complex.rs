@@ -0,0 +1,274 @@
+//! Functions of complex numbers.
+//!
+//! A complex number z = x + yi is passed as its two parts, `re` = x
+//! and `im` = y, and complex results are returned as `(re, im)`
+//! pairs, so these work with any representation of complex numbers.
+//!
+//! The error bounds below hold for `|im| <= 8192` (for `cexp`, `|im|`
+//! of its argument), the range of the reduction used for sine and
+//! cosine, which is the same as `tan`'s. Beyond that, results are
+//! finite but of unspecified accuracy.
+//!
+//! ```rust
+//! use fast_math::complex;
+//!
+//! // -1 = e^(iπ)
+//! let (re, im) = complex::clog(-1.0, 0.0);
+//! assert!(re.abs() < 1e-4 && (im - std::f32::consts::PI).abs() < 0.004);
+//! assert_eq!(complex::abs(3.0, 4.0), 5.0);
+//! ```
+
+use core::f32;
+use core::f32::consts::LN_2;
+use exp::exp_exact_anchors;
+use log::log2_accurate;
+use sinpi::sin_cos;
+
+/// From here up, e<sup>re</sup> is computed as the square of
+/// e<sup>re/2</sup>, as it may overflow even when the result doesn't.
+const SPLIT: f32 = 88.0;
+
+/// Compute a fast approximation of the argument (phase) of `re` +
+/// `im` i, in [-&pi;, &pi;].
+///
+/// This is `atan2(im, re)`, and the maximum absolute error is less
+/// than 0.0038.
+#[inline]
+pub fn arg(re: f32, im: f32) -> f32 {
+    ::atan2(im, re)
+}
+
+/// Compute a fast approximation of the absolute value (modulus) of
+/// `re` + `im` i.
+///
+/// This is `hypot(re, im)`: the maximum relative error is less than
+/// 3e-7, and there is no overflow or underflow unless the result
+/// itself is out of range. If either part is infinite, the result is
+/// &infin;, even if the other is NaN. Otherwise, if either is NaN,
+/// `abs` returns NaN.
+#[inline]
+pub fn abs(re: f32, im: f32) -> f32 {
+    ::hypot(re, im)
+}
+
+/// Compute a fast approximation of e<sup>z</sup>, for z = `re` +
+/// `im` i.
+///
+/// This is e<sup>re</sup> (cos(im) + i sin(im)), computed with
+/// `exp_exact_anchors`, and the maximum error of each part is less
+/// than 0.003 |e<sup>z</sup>| (or 0.006 |e<sup>z</sup>| for `re` &ge;
+/// 88, where e<sup>re</sup> is computed in two halves so that it
+/// doesn't overflow when the result doesn't), while the result is
+/// normal.
+///
+/// If `im` is 0, the result is exactly real, even when `re` is
+/// infinite. Otherwise, if `im` is infinite or either part is NaN,
+/// `cexp` returns NaN.
+#[inline]
+pub fn cexp(re: f32, im: f32) -> (f32, f32) {
+    if im == 0.0 {
+        // avoid ∞ × 0 = NaN for infinite `re`
+        return (exp_exact_anchors(re), im)
+    }
+    let (s, c) = sin_cos(im);
+    if re < SPLIT {
+        let e = exp_exact_anchors(re);
+        (e * c, e * s)
+    } else {
+        let h = exp_exact_anchors(0.5 * re);
+        (h * c * h, h * s * h)
+    }
+}
+
+/// Compute a fast approximation of the principal value of the natural
+/// logarithm of z = `re` + `im` i, that is, ln |z| + i arg(z).
+///
+/// The maximum absolute error of the real part is less than 2.5e-5,
+/// and that of the imaginary part is less than 0.0038, as in `arg`.
+///
+/// `clog(0, 0)` has real part -&infin;, and if either part is
+/// infinite, the real part is &infin;. Otherwise, if either part is
+/// NaN, `clog` returns NaN.
+#[inline]
+pub fn clog(re: f32, im: f32) -> (f32, f32) {
+    // the modulus and argument share a division
+    let (r, theta) = ::to_polar(re, im);
+    let ln_r = if r > 0.0 && r < f32::INFINITY {
+        LN_2 * log2_accurate(r)
+    } else if r == 0.0 {
+        f32::NEG_INFINITY
+    } else {
+        // ∞ or NaN
+        r
+    };
+    (ln_r, theta)
+}
+
+/// Compute a fast approximation of the principal value of
+/// z<sup>w</sup>, for z = `re` + `im` i and w = `w_re` + `w_im` i.
+///
+/// This is e<sup>w ln z</sup>, using `clog` and `cexp`. The error in
+/// w ln z is dominated by that of the argument of z, and so the
+/// maximum relative error of the result (compared to
+/// |z<sup>w</sup>|) is less than about 0.003 + 0.004 |w|.
+///
+/// z<sup>0</sup> is exactly 1 for every z, and 0<sup>w</sup> is
+/// exactly 0 when the real part of w is positive, and NaN otherwise.
+#[inline]
+pub fn powc(re: f32, im: f32, w_re: f32, w_im: f32) -> (f32, f32) {
+    if w_re == 0.0 && w_im == 0.0 {
+        return (1.0, 0.0)
+    }
+    if re == 0.0 && im == 0.0 {
+        return if w_re > 0.0 { (0.0, 0.0) } else { (f32::NAN, f32::NAN) }
+    }
+    let (l, t) = clog(re, im);
+    cexp(w_re * l - w_im * t, w_re * t + w_im * l)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+    use std::f32;
+
+    /// e^(x + yi) in f64.
+    fn exact_cexp(x: f64, y: f64) -> (f64, f64) {
+        let e = x.exp();
+        (e * y.cos(), e * y.sin())
+    }
+
+    /// ln(x + yi) in f64.
+    fn exact_clog(x: f64, y: f64) -> (f64, f64) {
+        (x.hypot(y).ln(), y.atan2(x))
+    }
+
+    #[test]
+    fn abs_rel_err_qc() {
+        fn prop(re: f32, im: f32) -> qc::TestResult {
+            if !re.is_finite() || !im.is_finite() { return qc::TestResult::discard() }
+            let t = (re as f64).hypot(im as f64);
+            let e = abs(re, im) as f64;
+            qc::TestResult::from_bool((e - t).abs() <= 3e-7 * t)
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn abs_rel_err_exhaustive() {
+        // every 31st ratio, at three scales, including ones where
+        // the squares overflow or underflow
+        let mut max = 0.0f64;
+        let mut t = 0.0f32;
+        while t <= 1.0 {
+            for &scale in &[1.0, 1e-30, 1e38] {
+                let (re, im) = (scale, t * scale);
+                let exact = (re as f64).hypot(im as f64);
+                let rel = (abs(re, im) as f64 - exact).abs() / exact;
+                if rel > max { max = rel }
+                assert!(rel <= 3e-7, "{:e} {:e}: {:e}", re, im, rel);
+            }
+            t = f32::from_bits(t.to_bits() + 31);
+        }
+        println!("maximum {:e}", max);
+    }
+
+    #[test]
+    fn cexp_err_qc() {
+        fn prop(re: f32, im: f32) -> qc::TestResult {
+            if !(re.abs() < 87.0 && im.abs() <= 8192.0) { return qc::TestResult::discard() }
+            let (tr, ti) = exact_cexp(re as f64, im as f64);
+            let (er, ei) = cexp(re, im);
+            let tol = 0.003 * tr.hypot(ti);
+            qc::TestResult::from_bool((er as f64 - tr).abs() <= tol && (ei as f64 - ti).abs() <= tol)
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn cexp_err_grid() {
+        let mut re = -87.0f32;
+        while re < 100.0 {
+            let mut im = -8192.0f32;
+            while im <= 8192.0 {
+                let (tr, ti) = exact_cexp(re as f64, im as f64);
+                let (er, ei) = cexp(re, im);
+                let tol = if re < SPLIT { 0.003 } else { 0.006 } * tr.hypot(ti);
+                // parts at the edge of the range of f32 can overflow
+                let close = |e: f32, t: f64| {
+                    (e as f64 - t).abs() <= tol || (t.abs() + tol > f32::MAX as f64 && e.is_infinite())
+                };
+                assert!(close(er, tr) && close(ei, ti),
+                        "{:e} {:e}: {:e} {:e}, {:e} {:e}", re, im, er, ei, tr, ti);
+                im += 1.37;
+            }
+            re += 0.53;
+        }
+    }
+
+    #[test]
+    fn clog_err_qc() {
+        fn prop(re: f32, im: f32) -> qc::TestResult {
+            if !re.is_finite() || !im.is_finite() || (re == 0.0 && im == 0.0) {
+                return qc::TestResult::discard()
+            }
+            let (tr, ti) = exact_clog(re as f64, im as f64);
+            let (er, ei) = clog(re, im);
+            qc::TestResult::from_bool((er as f64 - tr).abs() < 2.5e-5 + tr.abs() * f32::EPSILON as f64 &&
+                                      (ei as f64 - ti).abs() < 0.0038)
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn powc_rel_err_qc() {
+        fn prop(re: f32, im: f32, w_re: f32, w_im: f32) -> qc::TestResult {
+            let z_ok = re.is_finite() && im.is_finite() && (re != 0.0 || im != 0.0);
+            let w_ok = w_re.abs() <= 4.0 && w_im.abs() <= 4.0;
+            if !z_ok || !w_ok { return qc::TestResult::discard() }
+            // z^w = e^(w ln z), in f64
+            let (l, t) = exact_clog(re as f64, im as f64);
+            let (w_re, w_im) = (w_re as f64, w_im as f64);
+            let (ur, ui) = (w_re * l - w_im * t, w_re * t + w_im * l);
+            if ur.abs() > 80.0 { return qc::TestResult::discard() }
+            let (tr, ti) = exact_cexp(ur, ui);
+            let (er, ei) = powc(re, im, w_re as f32, w_im as f32);
+            let tol = (0.003 + 0.004 * w_re.hypot(w_im)) * tr.hypot(ti);
+            qc::TestResult::from_bool((er as f64 - tr).abs() <= tol && (ei as f64 - ti).abs() <= tol)
+        }
+        qc::quickcheck(prop as fn(f32, f32, f32, f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn edge_cases() {
+        let inf = f32::INFINITY;
+        let nan = f32::NAN;
+
+        assert_eq!(abs(0.0, 0.0), 0.0);
+        assert_eq!(abs(-3.0, 0.0), 3.0);
+        assert_eq!(abs(f32::MAX, f32::MAX), inf);
+        assert_eq!(abs(nan, -inf), inf);
+        assert!(abs(nan, 1.0).is_nan());
+
+        assert_eq!(cexp(0.0, 0.0), (1.0, 0.0));
+        assert_eq!(cexp(inf, 0.0), (inf, 0.0));
+        assert_eq!(cexp(-inf, 1.0), (0.0, 0.0));
+        // e^89 overflows, but e^89 cos(1.5) doesn't
+        assert!(cexp(89.0, 1.5).0.is_finite());
+        let (re, im) = cexp(1.0, inf);
+        assert!(re.is_nan() && im.is_nan());
+        let (re, im) = cexp(nan, 1.0);
+        assert!(re.is_nan() && im.is_nan());
+
+        assert_eq!(clog(0.0, 0.0).0, -inf);
+        assert_eq!(clog(1.0, 0.0), (0.0, 0.0));
+        assert_eq!(clog(-inf, nan).0, inf);
+        let (re, im) = clog(nan, 1.0);
+        assert!(re.is_nan() && im.is_nan());
+
+        assert_eq!(powc(nan, nan, 0.0, 0.0), (1.0, 0.0));
+        assert_eq!(powc(0.0, 0.0, 2.0, 1.0), (0.0, 0.0));
+        let (re, im) = powc(0.0, 0.0, -1.0, 0.0);
+        assert!(re.is_nan() && im.is_nan());
+    }
+}
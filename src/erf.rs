@@ -0,0 +1,346 @@
+use core::f32::consts::LN_2;
+use exp::{exp, exp_raw};
+use ieee754::Ieee754;
+use log::log2_accurate;
+use sqrt::sqrt_raw;
+
+/// Below this, `erf` is computed directly, and above it, via the
+/// tail of `erfc`.
+const TAIL: f32 = 2.0;
+
+/// The domain of the `_raw` functions: beyond this, `exp_raw(-x * x)`
+/// is out of range (and erf(x) is &plusmn;1 to `f32` precision).
+const RAW_LIMIT: f32 = 9.0;
+
+/// erf(x) for `|x| <= TAIL`, with absolute error 5.2e-6.
+#[inline]
+#[allow(clippy::excessive_precision)]
+fn erf_kernel(x: f32) -> f32 {
+    const C0: f32 = 1.12833896265;
+    const C1: f32 = -0.375726907269;
+    const C2: f32 = 0.111680755823;
+    const C3: f32 = -0.025361648431;
+    const C4: f32 = 0.00418372428358;
+    const C5: f32 = -0.000440908958146;
+    const C6: f32 = 2.17146388694e-5;
+    let s = x * x;
+    x * (C0 + s * (C1 + s * (C2 + s * (C3 + s * (C4 + s * (C5 + s * C6))))))
+}
+
+/// erfc(x) for `x >= TAIL`, given `exp_neg_x2` = e<sup>-x<sup>2</sup></sup>.
+///
+/// The polynomial approximates x e<sup>x<sup>2</sup></sup> erfc(x) in
+/// 1/x<sup>2</sup> with relative error 3.8e-5, so the error is
+/// dominated by that of `exp_neg_x2`.
+#[inline]
+#[allow(clippy::excessive_precision)]
+fn erfc_tail(x: f32, exp_neg_x2: f32) -> f32 {
+    const G0: f32 = 0.564168356111;
+    const G1: f32 = -0.27894805405;
+    const G2: f32 = 0.346271059595;
+    const G3: f32 = -0.339273571134;
+    let u = 1.0 / (x * x);
+    exp_neg_x2 * (G0 + u * (G1 + u * (G2 + u * G3))) / x
+}
+
+/// Compute a fast approximation of the error function for `|x| <= 9`.
+///
+/// This will return unspecified nonsense if `x` does not satisfy
+/// those requirements. Use `erf` if correct handling is required (at
+/// the expense of some speed).
+///
+/// The maximum absolute error across all valid input is less than
+/// 1.2e-5.
+#[inline]
+pub fn erf_raw(x: f32) -> f32 {
+    check_raw!(x.abs() <= RAW_LIMIT, "erf_raw", x, "[-9, 9]");
+    let a = x.abs();
+    if a < TAIL {
+        erf_kernel(x)
+    } else {
+        (1.0 - erfc_tail(a, exp_raw(-a * a))).copy_sign(x)
+    }
+}
+
+/// Compute a fast approximation of the error function of `x`.
+///
+/// The maximum absolute error across all f32s is less than 1.2e-5,
+/// and `erf(±∞)` is exactly &plusmn;1.
+///
+/// If `x` is NaN, `erf` returns NaN.
+///
+/// See also `erf_raw` which only works on `|x| <= 9`, but is faster.
+#[inline]
+pub fn erf(x: f32) -> f32 {
+    if x.abs() <= RAW_LIMIT {
+        erf_raw(x)
+    } else {
+        // 1 - erfc(|x|) rounds to 1, and NaN propagates
+        x.signum()
+    }
+}
+
+/// Compute a fast approximation of the complementary error function,
+/// 1 - erf(x), for `|x| <= 9`.
+///
+/// This will return unspecified nonsense if `x` does not satisfy
+/// those requirements. Use `erfc` if correct handling is required (at
+/// the expense of some speed).
+///
+/// The maximum absolute error across all valid input is less than
+/// 1.2e-5. For `x` &ge; 2, where the result is less than 0.005, the
+/// maximum relative error is less than 0.0021.
+#[inline]
+pub fn erfc_raw(x: f32) -> f32 {
+    check_raw!(x.abs() <= RAW_LIMIT, "erfc_raw", x, "[-9, 9]");
+    let a = x.abs();
+    if a < TAIL {
+        1.0 - erf_kernel(x)
+    } else {
+        let tail = erfc_tail(a, exp_raw(-a * a));
+        if x > 0.0 { tail } else { 2.0 - tail }
+    }
+}
+
+/// Compute a fast approximation of the complementary error function
+/// of `x`, 1 - erf(x).
+///
+/// The tail for `x` &ge; 2 is computed using `exp`, rather than by
+/// subtracting from 1, so that tiny results keep their relative
+/// accuracy: the maximum relative error there is less than 0.0021
+/// (for normal results), and the maximum absolute error across all
+/// f32s is less than 1.2e-5. `erfc(-∞)` is exactly 2 and `erfc(∞)`
+/// is exactly 0.
+///
+/// If `x` is NaN, `erfc` returns NaN.
+///
+/// See also `erfc_raw` which only works on `|x| <= 9`, but is
+/// faster.
+#[inline]
+pub fn erfc(x: f32) -> f32 {
+    if x.abs() <= RAW_LIMIT {
+        erfc_raw(x)
+    } else if x > 0.0 {
+        // tiny or zero: the result is subnormal from about x = 9.2, so
+        // scale up to keep `exp` normal, and round only once at the
+        // end (x * x overflowing to infinity is harmless)
+        const EXP2_32: f32 = 4294967296.0;
+        erfc_tail(x, exp(-x * x + 32.0 * LN_2)) * (1.0 / EXP2_32)
+    } else if x < 0.0 {
+        2.0
+    } else {
+        // NaN
+        x
+    }
+}
+
+/// erf<sup>-1</sup>(x) / x, given `y` = 1 - x<sup>2</sup> > 0, which
+/// can be computed more accurately than `x` when `|x|` is close to 1.
+///
+/// The maximum relative error is less than 3e-5.
+#[inline]
+#[allow(clippy::excessive_precision)]
+pub(crate) fn erf_inv_ratio(y: f32) -> f32 {
+    let w = -LN_2 * log2_accurate(y);
+    if w < 5.0 {
+        const C0: f32 = 0.886245953145;
+        const C1: f32 = 0.231688872379;
+        const C2: f32 = 0.0124039276155;
+        const C3: f32 = -0.00312634381232;
+        const C4: f32 = 0.000185265574248;
+        C0 + w * (C1 + w * (C2 + w * (C3 + w * C4)))
+    } else if w < 16.0 {
+        // the range needed for erf_inv of an f32
+        const C0: f32 = 2.8329905006;
+        const C1: f32 = 1.00172677575;
+        const C2: f32 = 0.00911617840597;
+        const C3: f32 = -0.00806023074178;
+        const C4: f32 = 0.0069691343361;
+        const C5: f32 = -0.00297918617506;
+        let t = sqrt_raw(w) - 3.0;
+        C0 + t * (C1 + t * (C2 + t * (C3 + t * (C4 + t * C5))))
+    } else {
+        // y is at least the smallest subnormal, so w < 104
+        const C0: f32 = 6.86892301806;
+        const C1: f32 = 1.00876298117;
+        const C2: f32 = -0.000424300559844;
+        const C3: f32 = -3.21741488754e-5;
+        let t = sqrt_raw(w) - 7.0;
+        C0 + t * (C1 + t * (C2 + t * C3))
+    }
+}
+
+/// Compute a fast approximation of the inverse error function for
+/// `|x| < 1`.
+///
+/// This will return unspecified nonsense if `x` does not satisfy
+/// those requirements. Use `erf_inv` if correct handling is required
+/// (at the expense of some speed).
+///
+/// The maximum relative error across all valid input is less than
+/// 3e-5.
+#[inline]
+pub fn erf_inv_raw(x: f32) -> f32 {
+    check_raw!(x.abs() < 1.0, "erf_inv_raw", x, "(-1, 1)");
+    let a = x.abs();
+    x * erf_inv_ratio((1.0 - a) * (1.0 + a))
+}
+
+/// Compute a fast approximation of the inverse error function of
+/// `x`, that is, the `y` with `erf(y) == x`.
+///
+/// The maximum relative error for `|x| < 1` is less than 3e-5, and
+/// `erf_inv(±1)` is &plusmn;&infin;.
+///
+/// If `x` is NaN, or `|x| > 1`, `erf_inv` returns NaN.
+///
+/// See also `erf_inv_raw` which only works on `|x| < 1`, but is
+/// faster.
+#[inline]
+pub fn erf_inv(x: f32) -> f32 {
+    let a = x.abs();
+    if a < 1.0 {
+        erf_inv_raw(x)
+    } else if a == 1.0 {
+        f32::INFINITY.copy_sign(x)
+    } else {
+        // out of range, or NaN
+        f32::NAN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+    use reference::{exact_erf, exact_erfc, exact_erf_inv};
+
+    const TOL: f64 = 1.2e-5;
+
+    #[test]
+    fn erf_erfc_abs_err_exhaustive() {
+        let mut max = 0.0f64;
+        let mut max_rel = 0.0f64;
+        let mut x = 0.0f32;
+        while x <= RAW_LIMIT {
+            for &x in &[x, -x] {
+                let (t, tc) = (exact_erf(x as f64), exact_erfc(x as f64));
+                let e = (erf(x) as f64 - t).abs();
+                let ec = (erfc(x) as f64 - tc).abs();
+                max = max.max(e).max(ec);
+                assert!(e < TOL, "erf({:e}) = {:e}, {:e}", x, erf(x), t);
+                assert!(ec < TOL, "erfc({:e}) = {:e}, {:e}", x, erfc(x), tc);
+                assert_eq!(erf(x).to_bits(), erf_raw(x).to_bits());
+                assert_eq!(erfc(x).to_bits(), erfc_raw(x).to_bits());
+                if x >= TAIL && tc >= f32::MIN_POSITIVE as f64 {
+                    max_rel = max_rel.max(ec / tc);
+                    assert!(ec / tc < 0.0021, "erfc({:e}) = {:e}, {:e}", x, erfc(x), tc);
+                }
+            }
+            x = f32::from_bits(x.to_bits() + 101);
+        }
+        println!("maximum {:e}, relative in tail {:e}", max, max_rel);
+    }
+
+    #[test]
+    fn erf_erfc_abs_err_qc() {
+        fn prop(x: f32) -> qc::TestResult {
+            if x.is_nan() { return qc::TestResult::discard() }
+            let e = (erf(x) as f64 - exact_erf(x as f64)).abs();
+            let ec = (erfc(x) as f64 - exact_erfc(x as f64)).abs();
+            qc::TestResult::from_bool(e < TOL && ec < TOL)
+        }
+        qc::quickcheck(prop as fn(f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn erfc_tail_rel_err() {
+        // the tail beyond the domain of the raw function
+        let mut x = RAW_LIMIT;
+        while x < 10.0 {
+            let t = exact_erfc(x as f64);
+            let e = erfc(x) as f64;
+            // subnormal results have extra absolute rounding error of
+            // up to half the smallest subnormal
+            let tol = 0.0021 * t + 0.5 * f32::MIN_POSITIVE as f64 * f32::EPSILON as f64;
+            assert!((e - t).abs() < tol, "{:e}: {:e} {:e}", x, e, t);
+            x = f32::from_bits(x.to_bits() + 101);
+        }
+    }
+
+    #[test]
+    fn erf_inv_rel_err_exhaustive() {
+        let mut max = 0.0f64;
+        let mut x = f32::MIN_POSITIVE;
+        while x < 1.0 {
+            for &x in &[x, -x] {
+                let e = erf_inv(x);
+                let t = exact_erf_inv(x, e);
+                let rel = ((e as f64 - t) / t).abs();
+                if rel > max { max = rel }
+                assert!(rel < 3e-5, "{:e}: {:e} {:e} {:e}", x, e, t, rel);
+                assert_eq!(e.to_bits(), erf_inv_raw(x).to_bits());
+            }
+            x = f32::from_bits(x.to_bits() + 1001);
+        }
+        // the closest floats to 1
+        let mut x = 1.0f32.prev();
+        for _ in 0..1000 {
+            let e = erf_inv(x);
+            let t = exact_erf_inv(x, e);
+            assert!(((e as f64 - t) / t).abs() < 3e-5, "{:e}: {:e} {:e}", x, e, t);
+            x = x.prev();
+        }
+        println!("maximum {:e}", max);
+    }
+
+    #[test]
+    fn erf_inv_round_trip() {
+        let mut x = -1.0f32.prev();
+        while x < 1.0 {
+            let y = erf(erf_inv(x));
+            assert!((y - x).abs() < 3e-5, "{} {}", x, y);
+            x += 0.0001;
+        }
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert!(erf(f32::NAN).is_nan());
+        assert!(erfc(f32::NAN).is_nan());
+        assert!(erf_inv(f32::NAN).is_nan());
+        assert_eq!(erf(f32::INFINITY), 1.0);
+        assert_eq!(erf(f32::NEG_INFINITY), -1.0);
+        assert_eq!(erfc(f32::INFINITY), 0.0);
+        assert_eq!(erfc(f32::NEG_INFINITY), 2.0);
+        assert_eq!(erf(1e30), 1.0);
+        assert_eq!(erfc(1e30), 0.0);
+        assert_eq!(erfc(-1e30), 2.0);
+        assert_eq!(erf(0.0).to_bits(), 0.0f32.to_bits());
+        assert_eq!(erf(-0.0).to_bits(), (-0.0f32).to_bits());
+        assert_eq!(erfc(0.0), 1.0);
+        assert_eq!(erf_inv(0.0).to_bits(), 0.0f32.to_bits());
+        assert_eq!(erf_inv(-0.0).to_bits(), (-0.0f32).to_bits());
+        assert_eq!(erf_inv(1.0), f32::INFINITY);
+        assert_eq!(erf_inv(-1.0), f32::NEG_INFINITY);
+        assert!(erf_inv(1.5).is_nan());
+        assert!(erf_inv(f32::INFINITY).is_nan());
+        let tiny = f32::from_bits(1);
+        assert!(erf(tiny) >= tiny);
+        assert!(erf_inv(tiny) > 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "strict-raw")]
+    fn raw_strict_domain() {
+        ::float::assert_raw_domains(&[
+            (erf_raw, &[-RAW_LIMIT, RAW_LIMIT], &[10.0, -9.5, f32::NAN]),
+            (erfc_raw, &[-RAW_LIMIT, RAW_LIMIT], &[10.0, -9.5, f32::NAN]),
+            (erf_inv_raw, &[-1.0f32.prev(), 1.0f32.prev()], &[-1.0, 1.0, f32::NAN]),
+        ]);
+        // the full functions never trip the checks
+        assert!(erf(f32::NAN).is_nan());
+        assert!(erfc(f32::NAN).is_nan());
+        assert!(erf_inv(f32::NAN).is_nan());
+    }
+}
@@ -1,7 +1,6 @@
 use core::f32;
 use core::f32::consts as f;
 use float;
-use ieee754::Ieee754;
 
 #[derive(Clone, Copy)]
 enum Base {
@@ -10,7 +9,7 @@ enum Base {
 }
 impl Base {
     #[inline(always)]
-    fn log2(self) -> f32 {
+    const fn log2(self) -> f32 {
         match self {
             Base::E => f::LOG2_E,
             Base::Two => 1.0,
@@ -26,32 +25,89 @@ impl Base {
     fn lower_limit(self) -> f32 {
         -127.0 / self.log2()
     }
+
+    /// Below this, the result is subnormal.
+    #[inline(always)]
+    fn normal_limit(self) -> f32 {
+        -126.0 / self.log2()
+    }
+
+    /// Below this, the result rounds to zero, even with some
+    /// approximation error.
+    #[inline(always)]
+    fn subnormal_limit(self) -> f32 {
+        -152.0 / self.log2()
+    }
 }
 
+/// How `exp_with_subnormals` and `exp2_with_subnormals` handle inputs
+/// for which the result is below the normal range of `f32`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Subnormals {
+    /// The behaviour of `exp` and `exp2`: fastest, but a subnormal
+    /// result can have relative error as large as 1, and results
+    /// below about half the normal range are 0.
+    Approximate,
+    /// Scale subnormal results properly, so that the only extra error
+    /// beyond that of normal results is the rounding to a subnormal,
+    /// all the way down to the smallest subnormal.
+    Accurate,
+    /// Return exactly 0 for every input for which the result would be
+    /// subnormal.
+    FlushToZero,
+}
+
+const EXP2_23: f32 = 1.1920929e-7;
+
+/// A quadratic approximating 2<sup>f</sup> on [0, 1), with `f` scaled
+/// by 2<sup>23</sup>.
+struct Poly {
+    c0: f32,
+    c1: f32,
+    c2: f32,
+}
+
+#[allow(clippy::excessive_precision)]
+const DEFAULT: Poly = Poly {
+    c0: 0.3371894346 * EXP2_23 * EXP2_23,
+    c1: 0.657636276 * EXP2_23,
+    c2: 1.00172476,
+};
+
+/// The minimax quadratic constrained to be exactly 1 at 0 and 2 at 1,
+/// so that integer powers are exact (and the pieces join up
+/// continuously).
+#[allow(clippy::excessive_precision)]
+const EXACT_ANCHORS: Poly = {
+    const B: f32 = 0.33976601945;
+    Poly {
+        c0: B * EXP2_23 * EXP2_23,
+        c1: (1.0 - B) * EXP2_23,
+        c2: 1.0,
+    }
+};
+
+/// Approximate `base^x * 2^shift`.
 #[inline(always)]
-fn exp_raw_impl(x: f32, base: Base) -> f32 {
+const fn exp_raw_impl(x: f32, base: Base, poly: &Poly, shift: i32) -> f32 {
     const A: f32 = (1 << float::SIGNIF) as f32;
     const MASK: i32 = 0xff800000u32 as i32;
-    const EXP2_23: f32 = 1.1920929e-7;
-    const C0: f32 = 0.3371894346 * EXP2_23 * EXP2_23;
-    const C1: f32 = 0.657636276 * EXP2_23;
-    const C2: f32 = 1.00172476;
 
     let a = A * base.log2();
-    let mul = (a * x) as i32;
+    let mul = (a * x) as i32 + (shift << float::SIGNIF);
     let floor = mul & MASK;
     let frac = (mul - floor) as f32;
 
-    let approx = (C0 * frac + C1) * frac + C2;
-    f32::from_bits(approx.bits().wrapping_add(floor as u32))
+    let approx = (poly.c0 * frac + poly.c1) * frac + poly.c2;
+    f32::from_bits(approx.to_bits().wrapping_add(floor as u32))
 }
 
 #[inline(always)]
-fn exp_impl(x: f32, base: Base) -> f32 {
+fn exp_impl(x: f32, base: Base, poly: &Poly) -> f32 {
     if x <= base.lower_limit() {
         0.0
     } else if x < base.upper_limit() {
-        exp_raw_impl(x, base)
+        exp_raw_impl(x, base, poly, 0)
     } else {
         // too big, or NaN, so lets overflow to infinity with some
         // arithmetic to propagate the NaN.
@@ -59,6 +115,35 @@ fn exp_impl(x: f32, base: Base) -> f32 {
     }
 }
 
+#[inline(always)]
+fn exp_sat_impl(x: f32, base: Base) -> f32 {
+    if x < base.upper_limit() {
+        // the approximation can round up past f32::MAX just below
+        // the limit
+        exp_impl(x, base, &DEFAULT).min(f32::MAX)
+    } else if x.is_nan() {
+        x
+    } else {
+        f32::MAX
+    }
+}
+
+#[inline(always)]
+#[allow(clippy::excessive_precision)]
+fn exp_subnormals_impl(x: f32, base: Base, mode: Subnormals) -> f32 {
+    if mode == Subnormals::Approximate || x.is_nan() || x >= base.normal_limit() {
+        // NaN ends up here too
+        exp_impl(x, base, &DEFAULT)
+    } else if mode == Subnormals::FlushToZero || x <= base.subnormal_limit() {
+        0.0
+    } else {
+        // compute a normal result, and then let the multiplication
+        // round it to a subnormal.
+        const EXP2_M64: f32 = 5.421010862427522e-20;
+        exp_raw_impl(x, base, &DEFAULT, 64) * EXP2_M64
+    }
+}
+
 /// Compute a fast approximation to 2<sup><code>x</code></sup> for
 /// -151 &le; `x` &le; 151.
 ///
@@ -69,9 +154,11 @@ fn exp_impl(x: f32, base: Base) -> f32 {
 /// The maximum relative error for inputs for which the result is
 /// normal (`x` &ge; -128) is less than 0.011. For `x` < -128, the
 /// relative error in the (subnormal) result can be as large as 1.
+/// It is monotone for inputs for which the result is normal.
 #[inline]
-pub fn exp2_raw(x: f32) -> f32 {
-    exp_raw_impl(x, Base::Two)
+pub const fn exp2_raw(x: f32) -> f32 {
+    check_raw!(x >= -151.0 && x <= 151.0, "exp2_raw", x, "[-151, 151]");
+    exp_raw_impl(x, Base::Two, &DEFAULT, 0)
 }
 
 /// Compute a fast approximation to 2<sup><code>x</code></sup>.
@@ -80,13 +167,18 @@ pub fn exp2_raw(x: f32) -> f32 {
 /// normal (`x` &ge; -128) is less than 0.011. For `x` < -128, the
 /// relative error in the (subnormal) result can be as large as 1.
 ///
+/// `exp2` is monotone: `exp2(x) <= exp2(y)` whenever `x <= y`.
+///
+/// See `exp2_with_subnormals` for accurate subnormal results, or
+/// flushing them to zero.
+///
 /// If `x` is NaN, `exp2` returns NaN.
 ///
 /// See also `exp2_raw` which only works on -151 &le; `x` &le; 151,
 /// but is % faster.
 #[inline]
 pub fn exp2(x: f32) -> f32 {
-    exp_impl(x, Base::Two)
+    exp_impl(x, Base::Two, &DEFAULT)
 }
 
 /// Compute a fast approximation to *e*<sup><code>x</code></sup> for
@@ -99,10 +191,12 @@ pub fn exp2(x: f32) -> f32 {
 /// The maximum relative error for inputs for which the result is
 /// normal (`x` &ge; -128 ln(2) &approx; -88.7) is less than
 /// 0.011. For `x` < -128 ln(2), the relative error in the (subnormal)
-/// result can be as large as 1.
+/// result can be as large as 1. It is monotone for inputs for which
+/// the result is normal.
 #[inline]
-pub fn exp_raw(x: f32) -> f32 {
-    exp_raw_impl(x, Base::E)
+pub const fn exp_raw(x: f32) -> f32 {
+    check_raw!(x >= -104.0 && x <= 104.0, "exp_raw", x, "[-104, 104]");
+    exp_raw_impl(x, Base::E, &DEFAULT, 0)
 }
 
 /// Compute a fast approximation to *e*<sup><code>x</code></sup>.
@@ -112,18 +206,201 @@ pub fn exp_raw(x: f32) -> f32 {
 /// 0.011. For `x` < -128 ln 2, the relative error in the (subnormal)
 /// result can be as large as 1.
 ///
+/// `exp` is monotone: `exp(x) <= exp(y)` whenever `x <= y`.
+///
+/// See `exp_with_subnormals` for accurate subnormal results, or
+/// flushing them to zero.
+///
 /// If `x` is NaN, `exp` returns NaN.
 ///
 /// See also `exp_raw` which only works on -104 &le; `x` &le; 104,
 /// but is % faster.
 #[inline]
 pub fn exp(x: f32) -> f32 {
-    exp_impl(x, Base::E)
+    exp_impl(x, Base::E, &DEFAULT)
+}
+
+/// Compute a fast approximation to 2<sup><code>x</code></sup> that
+/// is exact at integers.
+///
+/// This is as fast as `exp2`, but its approximation is constrained so
+/// that `exp2_exact_anchors(n)` is exactly 2<sup><code>n</code></sup>
+/// for every integer -126 &le; `n` &le; 127 (that is, whenever the
+/// result is normal). The price is accuracy elsewhere: the maximum
+/// relative error for inputs for which the result is normal (`x`
+/// &ge; -126) is less than 0.0028.
+///
+/// Like `exp2`, this is monotone.
+///
+/// If `x` is NaN, `exp2_exact_anchors` returns NaN.
+#[inline]
+pub fn exp2_exact_anchors(x: f32) -> f32 {
+    exp_impl(x, Base::Two, &EXACT_ANCHORS)
+}
+
+/// Compute 2<sup><code>x</code></sup> more accurately than `exp2`.
+///
+/// This splits `x` into integer and fractional parts like `exp2_raw`,
+/// but uses a longer polynomial for the fractional part, so the
+/// maximum relative error is less than 1.9e-7 for -126 &le; `x` < 128,
+/// and `exp2_precise(n)` is exactly 2<sup><code>n</code></sup> for
+/// every integer `n` in that range. It is used internally by
+/// functions whose errors are visible in their results, like `color`.
+///
+/// Below that range the result is 0, and above it, &infin;. If `x`
+/// is NaN, `exp2_precise` returns NaN.
+#[inline]
+#[allow(clippy::excessive_precision)]
+pub(crate) fn exp2_precise(x: f32) -> f32 {
+    // minimax approximation of 2^f on [0, 1], constrained to be
+    // exactly 1 at 0
+    const C1: f32 = 0.6931513063391709380503960185681095778036355;
+    const C2: f32 = 0.2401645068909134866519162864394784405633337;
+    const C3: f32 = 0.05579967082448087364203484999469300130144273;
+    const C4: f32 = 0.00901738868638294257857114463050905144465073;
+    const C5: f32 = 0.00186696486961041403727869785398073893775199;
+    if x < -126.0 {
+        0.0
+    } else if x < 128.0 {
+        let mut n = x as i32;
+        if n as f32 > x {
+            n -= 1
+        }
+        let f = x - n as f32;
+        let p = 1.0 + f * (C1 + f * (C2 + f * (C3 + f * (C4 + f * C5))));
+        p * f32::from_bits(((n + 127) as u32) << float::SIGNIF)
+    } else {
+        // too big, or NaN, which propagates
+        x + f32::INFINITY
+    }
+}
+
+/// Compute a fast approximation to 2<sup><code>x</code></sup>, with
+/// the handling of subnormal results chosen by `mode`.
+///
+/// For `x` &ge; -126, this is exactly `exp2(x)`. For smaller `x`:
+///
+/// - `Subnormals::Approximate` is also exactly `exp2(x)`,
+/// - `Subnormals::Accurate` gives a result with the same relative
+///   error as a normal one, before rounding to the nearest
+///   subnormal (which is an absolute error of at most 2<sup>-150</sup>),
+/// - `Subnormals::FlushToZero` gives 0.
+///
+/// All modes are monotone. If `x` is NaN, `exp2_with_subnormals`
+/// returns NaN.
+#[inline]
+pub fn exp2_with_subnormals(x: f32, mode: Subnormals) -> f32 {
+    exp_subnormals_impl(x, Base::Two, mode)
+}
+
+/// Compute a fast approximation to *e*<sup><code>x</code></sup>, with
+/// the handling of subnormal results chosen by `mode`.
+///
+/// For `x` &ge; -126 ln 2 &approx; -87.3, this is exactly
+/// `exp(x)`. For smaller `x`, `mode` behaves as described in
+/// `exp2_with_subnormals`.
+///
+/// All modes are monotone. If `x` is NaN, `exp_with_subnormals`
+/// returns NaN.
+#[inline]
+pub fn exp_with_subnormals(x: f32, mode: Subnormals) -> f32 {
+    exp_subnormals_impl(x, Base::E, mode)
+}
+
+/// Compute a fast approximation to *e*<sup><code>x</code></sup> that
+/// is exact at 0.
+///
+/// This is as fast as `exp`, but uses the same constrained
+/// approximation as `exp2_exact_anchors`, so that
+/// `exp_exact_anchors(0.0)` is exactly 1. The maximum relative error
+/// for inputs for which the result is normal (`x` &ge; -126 ln 2
+/// &approx; -87.3) is less than 0.0028.
+///
+/// Like `exp`, this is monotone.
+///
+/// If `x` is NaN, `exp_exact_anchors` returns NaN.
+#[inline]
+pub fn exp_exact_anchors(x: f32) -> f32 {
+    exp_impl(x, Base::E, &EXACT_ANCHORS)
+}
+
+/// Compute a fast approximation to 2<sup><code>x</code></sup> that
+/// saturates at `f32::MAX` instead of overflowing to infinity.
+///
+/// This is exactly `exp2(x)` whenever that is finite. For larger `x`
+/// (including positive infinity), the result is `f32::MAX`. Like
+/// `exp2`, results below the normal range underflow to 0, so the
+/// result is never infinite; use `exp_clamped` to keep it away from
+/// 0 too.
+///
+/// If `x` is NaN, `exp2_sat` returns NaN.
+#[inline]
+pub fn exp2_sat(x: f32) -> f32 {
+    exp_sat_impl(x, Base::Two)
+}
+
+/// Compute a fast approximation to *e*<sup><code>x</code></sup> that
+/// saturates at `f32::MAX` instead of overflowing to infinity.
+///
+/// This is exactly `exp(x)` whenever that is finite. For larger `x`
+/// (including positive infinity), the result is `f32::MAX`. Like
+/// `exp`, results below the normal range underflow to 0, so the
+/// result is never infinite; use `exp_clamped` to keep it away from
+/// 0 too.
+///
+/// If `x` is NaN, `exp_sat` returns NaN.
+#[inline]
+pub fn exp_sat(x: f32) -> f32 {
+    exp_sat_impl(x, Base::E)
+}
+
+/// Compute a fast approximation to *e*<sup><code>x</code></sup>,
+/// clamped to [`lo`, `hi`].
+///
+/// This is `exp_sat(x).clamp(lo, hi)`, for computations that need
+/// bounds on the result: for instance, `exp_clamped(x,
+/// f32::MIN_POSITIVE, f32::MAX)` is always a positive normal float,
+/// so its logarithm and reciprocal are finite.
+///
+/// If `x` is NaN, `exp_clamped` returns NaN.
+///
+/// # Panics
+///
+/// Panics if `lo > hi`, or either is NaN.
+#[inline]
+pub fn exp_clamped(x: f32, lo: f32, hi: f32) -> f32 {
+    exp_sat(x).clamp(lo, hi)
+}
+
+/// Compute a fast approximation to *e*<sup><code>x</code></sup> - 1.
+///
+/// Unlike `exp(x) - 1.0`, this keeps its relative accuracy for `x`
+/// near zero, where the result is much smaller than 1: the maximum
+/// relative error for |`x`| < 1 is less than 3e-5, and `expm1(0.0)`
+/// is exactly 0. Elsewhere, this is `exp(x) - 1.0`, and the maximum
+/// relative error is less than 0.004.
+///
+/// If `x` is NaN, `expm1` returns NaN.
+#[inline]
+#[allow(clippy::excessive_precision)]
+pub fn expm1(x: f32) -> f32 {
+    if x.abs() < 1.0 {
+        const C0: f32 = 0.4998756029;
+        const C1: f32 = 0.1666048808;
+        const C2: f32 = 0.04216694863;
+        const C3: f32 = 0.008583074838;
+        const C4: f32 = 0.001009436909;
+        x * (1.0 + x * (C0 + x * (C1 + x * (C2 + x * (C3 + x * C4)))))
+    } else {
+        // NaN ends up here, and propagates
+        exp(x) - 1.0
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ieee754::Ieee754;
     use std::{f32, num};
 
     const PREC: u32 = 1 << 19;
@@ -194,4 +471,299 @@ mod tests {
         assert!((exp2(0.0) - 1.0).abs() < 0.002);
         assert_eq!(exp2(f32::INFINITY), f32::INFINITY);
     }
+
+    #[test]
+    fn monotone() {
+        // every float in ranges covering several segment boundaries
+        let full = &[exp as fn(f32) -> f32, exp2, exp_exact_anchors, exp2_exact_anchors];
+        let raw = &[exp_raw as fn(f32) -> f32, exp2_raw];
+        for &(lo, hi, fs) in &[(-1.5, -0.5, &full[..]), (0.5, 1.5, &full[..]),
+                               (-129.0, -125.0, &full[..]), (125.0, 129.0, &full[..]),
+                               (-1.5, -0.5, &raw[..]), (0.5, 1.5, &raw[..])] {
+            for &f in fs {
+                let mut prev = f(lo);
+                for x in lo.next().upto(hi) {
+                    let e = f(x);
+                    assert!(e >= prev, "{:e}: {:e} < {:e}", x, e, prev);
+                    prev = e;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exact_anchors_rel_err_exhaustive() {
+        let mut max = 0.0;
+        for i in 0..PREC + 1 {
+            for j in -5..6 {
+                for &sign in &[-1.0, 1.0] {
+                    let x = sign * (1.0 + i as f32 / PREC as f32) * 2f32.powi(j * 2);
+                    for &(e, t) in &[(exp_exact_anchors(x), x.exp()),
+                                     (exp2_exact_anchors(x), x.exp2())] {
+                        let rel = e.rel_error(t).abs();
+                        if t < f32::MIN_POSITIVE {
+                            // subnormal should be approximately right
+                            assert!(rel <= 1.0 || e == 0.0,
+                                    "{:.8}: e = {:.8e}, t = {:.8e}. {:.4}", x, e, t, rel);
+                        } else {
+                            if rel > max { max = rel }
+                            // e == t handles the infinity case
+                            assert!(rel <= 0.0028 || e == t,
+                                    "{:.8}: e = {:.8e}, t = {:.8e}. {:.4}", x, e, t, rel);
+                        }
+                    }
+                }
+            }
+        }
+        println!("maximum {}", max);
+    }
+
+    #[test]
+    fn exact_anchors() {
+        for n in -126..128 {
+            let e = exp2_exact_anchors(n as f32);
+            assert_eq!(e, 2f32.powi(n), "{}", n);
+        }
+        assert_eq!(exp2_exact_anchors(128.0), f32::INFINITY);
+        assert_eq!(exp_exact_anchors(0.0), 1.0);
+        assert_eq!(exp_exact_anchors(-0.0), 1.0);
+    }
+
+    #[test]
+    fn exp2_precise_rel_err_exhaustive() {
+        for &sign in &[-1.0, 1.0] {
+            let mut a = f32::from_bits(1);
+            while a <= 126.0 || (sign > 0.0 && a < 128.0) {
+                let x = sign * a;
+                let e = exp2_precise(x) as f64;
+                let t = (x as f64).exp2();
+                assert!(((e - t) / t).abs() < 1.9e-7, "{:e}: {:e} {:e}", x, e, t);
+                a = f32::from_bits(a.to_bits() + 97);
+            }
+        }
+        for n in -126..128 {
+            assert_eq!(exp2_precise(n as f32), 2f32.powi(n), "{}", n);
+        }
+        assert_eq!(exp2_precise(-126.5), 0.0);
+        assert_eq!(exp2_precise(f32::NEG_INFINITY), 0.0);
+        assert_eq!(exp2_precise(128.0), f32::INFINITY);
+        assert!(exp2_precise(f32::NAN).is_nan());
+    }
+
+    #[test]
+    fn exact_anchors_edge_cases() {
+        for &f in &[exp_exact_anchors as fn(f32) -> f32, exp2_exact_anchors] {
+            assert!(f(f32::NAN).is_nan());
+            assert_eq!(f(f32::NEG_INFINITY), 0.0);
+            assert_eq!(f(f32::INFINITY), f32::INFINITY);
+        }
+    }
+
+    #[test]
+    fn subnormals_exhaustive() {
+        use super::Subnormals::*;
+        let cases = [(exp_with_subnormals as fn(f32, Subnormals) -> f32,
+                      exp as fn(f32) -> f32, Base::E),
+                     (exp2_with_subnormals, exp2, Base::Two)];
+        for &(f, normal, base) in &cases {
+            let lo = -153.0 / base.log2();
+            let hi = -125.0 / base.log2();
+            let mut prev = [0.0; 3];
+            for x in lo.upto(hi) {
+                let t = 2f64.powf(x as f64 * base.log2() as f64);
+                let modes = [f(x, Approximate), f(x, Accurate), f(x, FlushToZero)];
+                assert_eq!(modes[0].to_bits(), normal(x).to_bits());
+                if x >= base.normal_limit() {
+                    for &e in &modes {
+                        assert_eq!(e.to_bits(), normal(x).to_bits());
+                    }
+                } else {
+                    // half an ulp of the smallest subnormal
+                    let half = 2f64.powi(-150);
+                    let e = modes[1] as f64;
+                    assert!((e - t).abs() <= 0.002 * t + half,
+                            "{:e}: e = {:e}, t = {:e}", x, e, t);
+                    assert_eq!(modes[2].to_bits(), 0);
+                }
+                for (p, &e) in prev.iter_mut().zip(&modes) {
+                    assert!(e >= *p, "{:e}: {:e} < {:e}", x, e, p);
+                    *p = e;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn subnormals_edge_cases() {
+        use super::Subnormals::*;
+        for &mode in &[Approximate, Accurate, FlushToZero] {
+            for &f in &[exp_with_subnormals as fn(f32, Subnormals) -> f32, exp2_with_subnormals] {
+                assert!(f(f32::NAN, mode).is_nan());
+                assert_eq!(f(f32::NEG_INFINITY, mode), 0.0);
+                assert_eq!(f(-1e30, mode), 0.0);
+                assert_eq!(f(f32::INFINITY, mode), f32::INFINITY);
+                assert_eq!(f(0.5, mode), f(0.5, Approximate));
+            }
+        }
+        // exactly representable subnormals
+        for n in -149..-126 {
+            let e = exp2_with_subnormals(n as f32, Accurate);
+            let t = f32::from_bits(1 << (n + 149));
+            assert!(e.rel_error(t).abs() < 0.002, "{}", n);
+        }
+        assert_eq!(exp2_with_subnormals(-151.5, Accurate), 0.0);
+    }
+
+    #[test]
+    fn sat_matches_finite() {
+        for &(f, sat, base) in &[(exp as fn(f32) -> f32, exp_sat as fn(f32) -> f32, Base::E),
+                                 (exp2, exp2_sat, Base::Two)] {
+            // every float in a range around overflow
+            let lo = base.upper_limit() - 4.0;
+            let hi = base.upper_limit() + 4.0;
+            let mut prev = 0.0;
+            for x in lo.upto(hi) {
+                let e = f(x);
+                let s = sat(x);
+                if e.is_finite() {
+                    assert_eq!(s.to_bits(), e.to_bits(), "{:e}", x);
+                } else {
+                    assert_eq!(s, f32::MAX, "{:e}", x);
+                }
+                assert!(s >= prev, "{:e}: {:e} < {:e}", x, s, prev);
+                prev = s;
+            }
+            for &x in &[-1e30, -100.0, -1.0, 0.0, 1.0, 50.0] {
+                assert_eq!(sat(x).to_bits(), f(x).to_bits(), "{:e}", x);
+            }
+        }
+    }
+
+    #[test]
+    fn sat_edge_cases() {
+        for &f in &[exp_sat as fn(f32) -> f32, exp2_sat] {
+            assert!(f(f32::NAN).is_nan());
+            assert_eq!(f(f32::NEG_INFINITY), 0.0);
+            assert_eq!(f(f32::INFINITY), f32::MAX);
+            assert_eq!(f(1e30), f32::MAX);
+            assert_eq!(f(f32::MAX), f32::MAX);
+        }
+    }
+
+    #[test]
+    fn clamped() {
+        let (lo, hi) = (f32::MIN_POSITIVE, f32::MAX);
+        assert_eq!(exp_clamped(f32::NEG_INFINITY, lo, hi), lo);
+        assert_eq!(exp_clamped(-100.0, lo, hi), lo);
+        assert_eq!(exp_clamped(f32::INFINITY, lo, hi), hi);
+        assert_eq!(exp_clamped(100.0, lo, hi), hi);
+        assert_eq!(exp_clamped(1.0, lo, hi), exp(1.0));
+        assert!(exp_clamped(f32::NAN, lo, hi).is_nan());
+        assert_eq!(exp_clamped(-1.0, 0.5, 2.0), 0.5);
+        assert_eq!(exp_clamped(0.0, 0.5, 2.0), exp(0.0));
+        assert_eq!(exp_clamped(1.0, 0.5, 2.0), 2.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn clamped_invalid_bounds() {
+        exp_clamped(0.0, 2.0, 1.0);
+    }
+
+    #[test]
+    fn expm1_rel_err_exhaustive() {
+        let mut max = 0.0f64;
+        for i in 0..PREC + 1 {
+            for j in -12..4 {
+                for &sign in &[-1.0, 1.0] {
+                    let x = sign * (1.0 + i as f32 / PREC as f32) * 2f32.powi(j * 2);
+                    let e = expm1(x) as f64;
+                    let t = (x as f64).exp_m1();
+                    let rel = ((e - t) / t).abs();
+                    if rel > max && e.is_finite() { max = rel }
+                    let tol = if x.abs() < 1.0 { 3e-5 } else { 0.004 };
+                    // equality handles overflow to infinity
+                    assert!(rel < tol || e as f32 == t as f32,
+                            "{:.8}: e = {:.8e}, t = {:.8e}. {:.4e}", x, e, t, rel);
+                }
+            }
+        }
+        println!("maximum {}", max);
+    }
+
+    #[test]
+    fn expm1_tiny() {
+        let mut x = f32::MIN_POSITIVE;
+        while x < 1.0 {
+            for &x in &[x, -x] {
+                let rel = ((expm1(x) as f64 - (x as f64).exp_m1()) / (x as f64).exp_m1()).abs();
+                assert!(rel < 3e-5, "{:e}: {:e}", x, rel);
+            }
+            x = f32::from_bits(x.to_bits() + 9973);
+        }
+    }
+
+    #[test]
+    fn expm1_edge_cases() {
+        assert!(expm1(f32::NAN).is_nan());
+        assert_eq!(expm1(f32::NEG_INFINITY), -1.0);
+        assert_eq!(expm1(-200.0), -1.0);
+        assert_eq!(expm1(0.0).to_bits(), 0.0f32.to_bits());
+        assert_eq!(expm1(-0.0).to_bits(), (-0.0f32).to_bits());
+        assert_eq!(expm1(f32::INFINITY), f32::INFINITY);
+        assert_eq!(expm1(200.0), f32::INFINITY);
+        let tiny = f32::from_bits(1);
+        assert_eq!(expm1(tiny), tiny);
+    }
+
+    const N: usize = 1 << 12;
+
+    #[test]
+    fn exp_raw_const() {
+        const fn input(i: usize) -> f32 {
+            -104.0 + 208.0 * (i as f32 / N as f32)
+        }
+        static TABLE: [f32; N] = {
+            let mut table = [0.0; N];
+            let mut i = 0;
+            while i < N {
+                table[i] = exp_raw(input(i));
+                i += 1;
+            }
+            table
+        };
+        for (i, &e) in TABLE.iter().enumerate() {
+            let x = input(i);
+            assert_eq!(e.to_bits(), exp_raw(x).to_bits(), "{}", x);
+        }
+    }
+
+    #[test]
+    fn exp2_raw_const() {
+        const fn input(i: usize) -> f32 {
+            -151.0 + 302.0 * (i as f32 / N as f32)
+        }
+        static TABLE: [f32; N] = {
+            let mut table = [0.0; N];
+            let mut i = 0;
+            while i < N {
+                table[i] = exp2_raw(input(i));
+                i += 1;
+            }
+            table
+        };
+        for (i, &e) in TABLE.iter().enumerate() {
+            let x = input(i);
+            assert_eq!(e.to_bits(), exp2_raw(x).to_bits(), "{}", x);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "strict-raw")]
+    fn raw_strict_domain() {
+        ::float::assert_raw_domains(&[
+            (exp2_raw, &[-151.0, 151.0], &[-151.5, 151.5, f32::NAN]),
+            (exp_raw, &[-104.0, 104.0], &[-104.5, 104.5, f32::NAN]),
+        ]);
+    }
 }
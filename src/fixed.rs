@@ -0,0 +1,578 @@
+//! Integer-only approximations on fixed-point numbers, for targets
+//! without a floating point unit.
+//!
+//! The `Q16_16`, `Q1_15` and `Q1_31` types are thin wrappers around
+//! the raw integer representation, with conversions to and from
+//! `f32` for convenience (and testing). Apart from those conversions,
+//! `from_f32` and `to_f32`, none of the functions in this module use
+//! floating point arithmetic.
+//!
+//! Each function is provided for every format: `log2`, `exp2` and
+//! `atan2` on `Q16_16`, `log2_q15`, `exp2_q15` and `atan2_q15` on
+//! `Q1_15`, and `log2_q31`, `exp2_q31` and `atan2_q31` on `Q1_31`,
+//! along with `atan2_binary` on plain integers. The logarithms of
+//! the `Q1_x` formats are in [-31, 0), so they are returned as
+//! `Q16_16`, and their `exp2` takes only non-positive `x`, where the
+//! result is at most 1.
+//!
+//! ```rust
+//! use fast_math::fixed::{self, Q16_16};
+//!
+//! let x = Q16_16::from_f32(10.4781);
+//! let approx = fixed::log2(x).to_f32();
+//! let real = 10.4781f32.log2();
+//! assert!((approx - real).abs() < 2e-5);
+//! ```
+
+macro_rules! fixed_type {
+    ($name: ident, $int: ty, $frac_bits: expr, $doc: expr) => {
+        #[doc = $doc]
+        ///
+        /// The wrapped integer is the raw representation: the number
+        /// multiplied by 2<sup>fractional bits</sup>.
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub $int);
+
+        impl $name {
+            /// The smallest (most negative) representable value.
+            pub const MIN: $name = $name(<$int>::MIN);
+            /// The largest representable value.
+            pub const MAX: $name = $name(<$int>::MAX);
+            /// The smallest positive representable value.
+            pub const EPSILON: $name = $name(1);
+
+            /// Convert `x` to the nearest representable value,
+            /// saturating at `MIN` and `MAX`. NaN is converted to
+            /// zero.
+            #[inline]
+            pub fn from_f32(x: f32) -> $name {
+                use ieee754::Ieee754;
+                // `as` truncates and saturates, so shift by a half
+                // first to round. Both steps are exact in f64 for
+                // every value that doesn't saturate, so this rounds
+                // only once.
+                let x = x as f64 * (1u64 << $frac_bits) as f64;
+                $name((x + 0.5f64.copy_sign(x)) as $int)
+            }
+
+            /// Convert to the nearest `f32`.
+            #[inline]
+            pub fn to_f32(self) -> f32 {
+                self.0 as f32 / (1u64 << $frac_bits) as f32
+            }
+        }
+    }
+}
+
+fixed_type!(Q16_16, i32, 16,
+            "A signed fixed-point number with 16 integer bits and 16 fractional bits.");
+fixed_type!(Q1_15, i16, 15,
+            "A signed fixed-point number in [-1, 1) with 15 fractional bits.");
+fixed_type!(Q1_31, i32, 31,
+            "A signed fixed-point number in [-1, 1) with 31 fractional bits.");
+
+impl Q16_16 {
+    /// The value 1.
+    pub const ONE: Q16_16 = Q16_16(1 << 16);
+}
+
+/// Evaluate `f * (c[0] + f * (c[1] + ...))`, with `c` in Q2.30 and
+/// `f` in Q0.32, giving a result in Q2.30.
+#[inline(always)]
+fn horner(c: &[i64], f: i64) -> i64 {
+    let mut p = 0;
+    for &c in c.iter().rev() {
+        p = c + ((p * f) >> 32);
+    }
+    (p * f) >> 32
+}
+
+/// log<sub>2</sub> of positive `raw` / 2<sup>`frac_bits`</sup>, in
+/// Q16.16.
+#[inline(always)]
+fn log2_kernel(raw: u32, frac_bits: i32) -> Q16_16 {
+    // minimax polynomial for log2(1 + f) / f on [0, 1], in Q2.30.
+    const C: [i64; 7] = [1549059304, -773772400, 508436211, -349010790,
+                         206272231, -83111262, 15868531];
+
+    // raw = 2^msb (1 + f)
+    let msb = 31 - raw.leading_zeros() as i32;
+    let f = ((raw as u64) << (32 - msb)) as u32;
+    let frac = horner(&C, f as i64);
+    Q16_16(((msb - frac_bits) << 16) + ((frac + (1 << 13)) >> 14) as i32)
+}
+
+/// 2<sup>f</sup> in Q2.30, for `f` in Q0.32, with relative error less
+/// than 1e-7.
+#[inline(always)]
+fn exp2_kernel(f: i64) -> i64 {
+    // minimax polynomial for (2^f - 1) / f on [0, 1], in Q2.30.
+    const C: [i64; 5] = [744265548, 257874676, 59914440, 9682347, 2004638];
+    (1 << 30) + horner(&C, f)
+}
+
+/// Compute an approximation of the base-2 logarithm of `x`.
+///
+/// The maximum absolute error for all positive `x` is less than
+/// 1.2e-5, that is, less than one unit in the last place of
+/// `Q16_16`.
+///
+/// If `x` is zero or negative, `log2` saturates to `Q16_16::MIN`.
+#[inline]
+pub fn log2(x: Q16_16) -> Q16_16 {
+    if x.0 <= 0 { Q16_16::MIN } else { log2_kernel(x.0 as u32, 16) }
+}
+
+/// Compute an approximation of the base-2 logarithm of `x`, as a
+/// `Q16_16`, since the result is in [-15, 0).
+///
+/// This has the same accuracy as `log2`. If `x` is zero or negative,
+/// `log2_q15` saturates to `Q16_16::MIN`.
+#[inline]
+pub fn log2_q15(x: Q1_15) -> Q16_16 {
+    if x.0 <= 0 { Q16_16::MIN } else { log2_kernel(x.0 as u32, 15) }
+}
+
+/// Compute an approximation of the base-2 logarithm of `x`, as a
+/// `Q16_16`, since the result is in [-31, 0).
+///
+/// This has the same accuracy as `log2`. If `x` is zero or negative,
+/// `log2_q31` saturates to `Q16_16::MIN`.
+#[inline]
+pub fn log2_q31(x: Q1_31) -> Q16_16 {
+    if x.0 <= 0 { Q16_16::MIN } else { log2_kernel(x.0 as u32, 31) }
+}
+
+/// Compute an approximation of 2<sup><code>x</code></sup>.
+///
+/// The maximum relative error is less than 1e-7, before the result is
+/// rounded to the nearest `Q16_16`.
+///
+/// If the result is too large to represent (`x` &ge; 15), `exp2`
+/// saturates to `Q16_16::MAX`, and if it is smaller than half of
+/// `Q16_16::EPSILON` (`x` < -17), `exp2` returns zero.
+#[inline]
+pub fn exp2(x: Q16_16) -> Q16_16 {
+    // x = n + f
+    let n = x.0 >> 16;
+    let f = (x.0 & 0xFFFF) as i64;
+    if n >= 15 {
+        return Q16_16::MAX
+    } else if n < -17 {
+        return Q16_16(0)
+    }
+    let p = exp2_kernel(f << 16);
+    // p is 2^f in Q2.30, so 2^x in Q16.16 is p 2^(n - 14).
+    let shift = 14 - n;
+    let raw = if shift == 0 { p } else { (p + (1 << (shift - 1))) >> shift };
+    Q16_16(raw.min(i32::MAX as i64) as i32)
+}
+
+/// Compute an approximation of 2<sup><code>x</code></sup> for
+/// non-positive `x`, where the result is in [1/2, 1].
+///
+/// The maximum relative error is less than 1e-7, before the result is
+/// rounded to the nearest `Q1_15`. For `x` &ge; 0, the result is at
+/// least 1, which is not representable, so `exp2_q15` saturates to
+/// `Q1_15::MAX`.
+#[inline]
+pub fn exp2_q15(x: Q1_15) -> Q1_15 {
+    if x.0 >= 0 {
+        return Q1_15::MAX
+    }
+    // x = -1 + f, and 2^x is p / 2 in Q2.30, that is, p in Q1.31
+    let f = (x.0 as i64 + (1 << 15)) << 17;
+    let p = exp2_kernel(f);
+    Q1_15(((p + (1 << 15)) >> 16).min(i16::MAX as i64) as i16)
+}
+
+/// Compute an approximation of 2<sup><code>x</code></sup> for
+/// non-positive `x`, where the result is in [1/2, 1].
+///
+/// The maximum relative error is less than 1e-7. For `x` &ge; 0, the
+/// result is at least 1, which is not representable, so `exp2_q31`
+/// saturates to `Q1_31::MAX`.
+#[inline]
+pub fn exp2_q31(x: Q1_31) -> Q1_31 {
+    if x.0 >= 0 {
+        return Q1_31::MAX
+    }
+    // x = -1 + f, and 2^x is p / 2 in Q2.30, that is, p in Q1.31
+    let f = (x.0 as i64 + (1 << 31)) << 1;
+    Q1_31(exp2_kernel(f).min(i32::MAX as i64) as i32)
+}
+
+/// atan(2<sup>-i</sup>) as a binary angle, where 2<sup>32</sup> is a
+/// full turn.
+const CORDIC_ANGLES: [u32; 24] = [
+    536870912, 316933406, 167458907, 85004756, 42667331, 21354465,
+    10679838, 5340245, 2670163, 1335087, 667544, 333772,
+    166886, 83443, 41722, 20861, 10430, 5215,
+    2608, 1304, 652, 326, 163, 81,
+];
+
+/// Compute |atan2(`y`, `x`)| as a binary angle in [0, 2<sup>31</sup>],
+/// where 2<sup>32</sup> is a full turn.
+///
+/// This uses the same octant reduction as the floating point
+/// `atan2`, followed by CORDIC on the first octant, which only needs
+/// shifts and additions (no division, which isn't available in
+/// hardware on many FPU-less targets).
+#[inline]
+fn atan2_binary_abs(y: i32, x: i32) -> u32 {
+    const QUARTER: u32 = 1 << 30;
+    const HALF: u32 = 1 << 31;
+
+    let (ax, ay) = (x.unsigned_abs(), y.unsigned_abs());
+    // reduce to 0 <= num / den <= 1
+    let (num, den) = if ay > ax { (ax, ay) } else { (ay, ax) };
+    let octant = if num == 0 {
+        // exactly on an axis (or the origin)
+        0
+    } else {
+        // scale so that den is in [2^28, 2^29), leaving room for the
+        // growth of the CORDIC iterations.
+        let s = den.leading_zeros() as i32 - 3;
+        let (mut cx, mut cy) = if s >= 0 {
+            ((den << s) as i32, (num << s) as i32)
+        } else {
+            ((den >> -s) as i32, (num >> -s) as i32)
+        };
+        let mut z = 0i32;
+        for (i, &angle) in CORDIC_ANGLES.iter().enumerate() {
+            let (dx, dy) = (cy >> i, cx >> i);
+            if cy > 0 {
+                cx += dx;
+                cy -= dy;
+                z += angle as i32;
+            } else {
+                cx -= dx;
+                cy += dy;
+                z -= angle as i32;
+            }
+        }
+        z.max(0) as u32
+    };
+
+    let angle = if ay > ax { QUARTER - octant } else { octant };
+    if x < 0 { HALF - angle } else { angle }
+}
+
+/// Compute an approximation of the four quadrant arctangent of
+/// integers `y` and `x`, as a binary angle, where 2<sup>32</sup> is a
+/// full turn.
+///
+/// The angle is measured anticlockwise from the positive `x` axis, so
+/// `atan2_binary(y, x) as i32` is the angle in [-&pi;, &pi;), in
+/// units of &pi; 2<sup>-31</sup>. Every `i32` (including `i32::MIN`)
+/// is handled exactly, and `atan2_binary(0, 0)` is 0.
+///
+/// The maximum absolute error is less than 2e-7 &pi;, that is, 107
+/// units.
+#[inline]
+pub fn atan2_binary(y: i32, x: i32) -> u32 {
+    let angle = atan2_binary_abs(y, x);
+    if y < 0 { angle.wrapping_neg() } else { angle }
+}
+
+/// Compute an approximation of the four quadrant arctangent of `y`
+/// and `x`, in radians.
+///
+/// The maximum absolute error is less than 2e-7, before the result is
+/// rounded to the nearest `Q16_16`. Like the floating point `atan2`,
+/// the result is in [-&pi;, &pi;], and `atan2(0, 0)` is 0.
+#[inline]
+pub fn atan2(y: Q16_16, x: Q16_16) -> Q16_16 {
+    // pi in Q3.29
+    const PI: u64 = 1686629713;
+    let angle = atan2_binary_abs(y.0, x.0) as u64;
+    // angle 2 pi / 2^32 in Q16.16
+    let rad = ((angle * PI + (1 << 43)) >> 44) as i32;
+    Q16_16(if y.0 < 0 { -rad } else { rad })
+}
+
+/// Compute an approximation of the four quadrant arctangent of `y`
+/// and `x`, in units of &pi; (half turns).
+///
+/// The maximum absolute error is less than 2e-7 &pi;, before the
+/// result is rounded to the nearest `Q1_15`.
+///
+/// An angle of &pi; (when `y` is zero and `x` is negative) is not
+/// representable, and wraps around to -1, that is, -&pi;, which is
+/// the same direction.
+#[inline]
+pub fn atan2_q15(y: Q1_15, x: Q1_15) -> Q1_15 {
+    let angle = (atan2_binary_abs(y.0 as i32, x.0 as i32) + (1 << 15)) >> 16;
+    let angle = angle as i16;
+    Q1_15(if y.0 < 0 { angle.wrapping_neg() } else { angle })
+}
+
+/// Compute an approximation of the four quadrant arctangent of `y`
+/// and `x`, in units of &pi; (half turns).
+///
+/// The maximum absolute error is less than 2e-7 &pi;.
+///
+/// An angle of &pi; (when `y` is zero and `x` is negative) is not
+/// representable, and wraps around to -1, that is, -&pi;, which is
+/// the same direction.
+#[inline]
+pub fn atan2_q31(y: Q1_31, x: Q1_31) -> Q1_31 {
+    Q1_31(atan2_binary(y.0, x.0) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+    use std::f64::consts::PI;
+    use ieee754::Ieee754;
+
+    const LSB: f64 = 1.0 / 65536.0;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Q16_16::from_f32(1.0), Q16_16::ONE);
+        assert_eq!(Q16_16::from_f32(-1.5).0, -3 << 15);
+        assert_eq!(Q16_16::from_f32(1e10), Q16_16::MAX);
+        assert_eq!(Q16_16::from_f32(-1e10), Q16_16::MIN);
+        assert_eq!(Q16_16::from_f32(f32::NAN), Q16_16(0));
+        assert_eq!(Q16_16::from_f32(0.4 / 65536.0), Q16_16(0));
+        assert_eq!(Q16_16::from_f32(0.6 / 65536.0), Q16_16(1));
+        assert_eq!(Q16_16(-3 << 15).to_f32(), -1.5);
+
+        assert_eq!(Q1_15::from_f32(0.5), Q1_15(1 << 14));
+        assert_eq!(Q1_15::from_f32(1.0), Q1_15::MAX);
+        assert_eq!(Q1_15::from_f32(-1.0), Q1_15::MIN);
+        assert_eq!(Q1_15::MIN.to_f32(), -1.0);
+
+        assert_eq!(Q1_31::from_f32(-0.25), Q1_31(-1 << 29));
+        assert_eq!(Q1_31::from_f32(2.0), Q1_31::MAX);
+        assert_eq!(Q1_31::MIN.to_f32(), -1.0);
+    }
+
+    #[test]
+    fn from_f32_qc() {
+        fn prop(bits: u32, small: f32) -> bool {
+            // the nearest value, saturating, rounding ties away from
+            // zero
+            fn nearest(x: f32, frac_bits: i32, min: f64, max: f64) -> f64 {
+                if x.is_nan() { 0.0 } else { (x as f64 * 2f64.powi(frac_bits)).round().clamp(min, max) }
+            }
+            [f32::from_bits(bits), small].iter().all(|&x| {
+                Q16_16::from_f32(x).0 as f64 == nearest(x, 16, i32::MIN as f64, i32::MAX as f64)
+                    && Q1_15::from_f32(x).0 as f64 == nearest(x, 15, i16::MIN as f64, i16::MAX as f64)
+                    && Q1_31::from_f32(x).0 as f64 == nearest(x, 31, i32::MIN as f64, i32::MAX as f64)
+            })
+        }
+        qc::quickcheck(prop as fn(u32, f32) -> bool);
+
+        // beyond 2^24 ulps, where rounding in f32 would round twice,
+        // and either side of saturation
+        let mut cases = vec![0.49999997 / 65536.0, 8388609.0 / 65536.0, 0.5 / 65536.0,
+                             1.0, -1.0, 32768.0, -32768.0, f32::INFINITY, f32::NEG_INFINITY];
+        for &x in &[32768.0f32, -32768.0, 1.0, -1.0] {
+            let (mut lo, mut hi) = (x, x);
+            for _ in 0..1000 {
+                lo = lo.prev();
+                hi = hi.next();
+                cases.push(lo);
+                cases.push(hi);
+            }
+        }
+        for &x in &cases {
+            assert!(prop(x.to_bits(), 0.0), "{:e}", x);
+        }
+        assert_eq!(Q16_16::from_f32(8388609.0 / 65536.0).0, 8388609);
+        assert_eq!(Q16_16::from_f32(0.49999997 / 65536.0).0, 0);
+    }
+
+    #[test]
+    fn log2_abs_err_exhaustive() {
+        let mut max = 0.0;
+        for raw in (1..i32::MAX).step_by(97).chain(1..1 << 16) {
+            let e = log2(Q16_16(raw)).0 as f64 * LSB;
+            let t = (raw as f64 * LSB).log2();
+            let abs = (e - t).abs();
+            if abs > max { max = abs }
+            assert!(abs < 1.2e-5, "{}: {:.8}, {:.8}. {:.4e}", raw, e, t, abs);
+        }
+        println!("maximum {:e}", max);
+    }
+
+    #[test]
+    fn log2_edge_cases() {
+        assert_eq!(log2(Q16_16(0)), Q16_16::MIN);
+        assert_eq!(log2(Q16_16(-1)), Q16_16::MIN);
+        assert_eq!(log2(Q16_16::MIN), Q16_16::MIN);
+        assert_eq!(log2(Q16_16::EPSILON), Q16_16::from_f32(-16.0));
+        assert_eq!(log2(Q16_16::ONE), Q16_16(0));
+        for i in 0..15 {
+            assert_eq!(log2(Q16_16(1 << (16 + i))), Q16_16(i << 16));
+        }
+    }
+
+    #[test]
+    fn exp2_rel_err_exhaustive() {
+        let mut max = 0.0;
+        for raw in (-17 << 16..15 << 16).step_by(7) {
+            let e = exp2(Q16_16(raw)).0 as f64 * LSB;
+            let t = (raw as f64 * LSB).exp2();
+            let rel = (e - t).abs() / t;
+            if t >= 1.0 && rel > max { max = rel }
+            assert!((e - t).abs() <= 1e-7 * t + LSB / 2.0,
+                    "{}: {:.8}, {:.8}. {:.4e}", raw, e, t, rel);
+        }
+        println!("maximum {:e}", max);
+    }
+
+    #[test]
+    fn exp2_edge_cases() {
+        assert_eq!(exp2(Q16_16(0)), Q16_16::ONE);
+        assert_eq!(exp2(Q16_16::from_f32(15.0)), Q16_16::MAX);
+        assert_eq!(exp2(Q16_16::MAX), Q16_16::MAX);
+        assert_eq!(exp2(Q16_16::from_f32(-17.0)), Q16_16(1));
+        assert_eq!(exp2(Q16_16::from_f32(-17.5)), Q16_16(0));
+        assert_eq!(exp2(Q16_16::MIN), Q16_16(0));
+        for i in -16..15 {
+            assert_eq!(exp2(Q16_16(i << 16)).0, 1 << (16 + i));
+        }
+    }
+
+    #[test]
+    fn log2_q15_q31_abs_err_exhaustive() {
+        for raw in 1..=i16::MAX {
+            let e = log2_q15(Q1_15(raw)).0 as f64 * LSB;
+            let t = (raw as f64 / 32768.0).log2();
+            assert!((e - t).abs() < 1.2e-5, "{}: {:.8}, {:.8}", raw, e, t);
+        }
+        for raw in (1..i32::MAX).step_by(97).chain(1..1 << 16) {
+            let e = log2_q31(Q1_31(raw)).0 as f64 * LSB;
+            let t = (raw as f64 / 2f64.powi(31)).log2();
+            assert!((e - t).abs() < 1.2e-5, "{}: {:.8}, {:.8}", raw, e, t);
+        }
+    }
+
+    #[test]
+    fn exp2_q15_q31_rel_err_exhaustive() {
+        for raw in i16::MIN..0 {
+            let e = exp2_q15(Q1_15(raw)).0 as f64 / 32768.0;
+            let t = (raw as f64 / 32768.0).exp2();
+            assert!((e - t).abs() <= 1e-7 * t + 0.5 / 32768.0, "{}: {:.8}, {:.8}", raw, e, t);
+        }
+        for raw in (i32::MIN..0).step_by(97) {
+            let e = exp2_q31(Q1_31(raw)).0 as f64 / 2f64.powi(31);
+            let t = (raw as f64 / 2f64.powi(31)).exp2();
+            assert!((e - t).abs() <= 1e-7 * t, "{}: {:.10}, {:.10}", raw, e, t);
+        }
+    }
+
+    #[test]
+    fn q15_q31_edge_cases() {
+        for &x in &[0, -1, i16::MIN] {
+            assert_eq!(log2_q15(Q1_15(x)), Q16_16::MIN);
+        }
+        for &x in &[0, -1, i32::MIN] {
+            assert_eq!(log2_q31(Q1_31(x)), Q16_16::MIN);
+        }
+        assert_eq!(log2_q15(Q1_15::EPSILON), Q16_16::from_f32(-15.0));
+        assert_eq!(log2_q31(Q1_31::EPSILON), Q16_16::from_f32(-31.0));
+        for i in 0..15 {
+            assert_eq!(log2_q15(Q1_15(1 << i)), Q16_16((i - 15) << 16));
+        }
+        for i in 0..31 {
+            assert_eq!(log2_q31(Q1_31(1 << i)), Q16_16((i - 31) << 16));
+        }
+
+        assert_eq!(exp2_q15(Q1_15::MIN), Q1_15(1 << 14));
+        assert_eq!(exp2_q31(Q1_31::MIN), Q1_31(1 << 30));
+        for &x in &[0, 1, i16::MAX] {
+            assert_eq!(exp2_q15(Q1_15(x)), Q1_15::MAX);
+        }
+        for &x in &[0, 1, i32::MAX] {
+            assert_eq!(exp2_q31(Q1_31(x)), Q1_31::MAX);
+        }
+        // just below 0, the result rounds up to 1, and saturates
+        assert_eq!(exp2_q15(Q1_15(-1)), Q1_15::MAX);
+    }
+
+    fn check_atan2(y: i32, x: i32) -> bool {
+        let t = (y as f64).atan2(x as f64);
+        let e = atan2(Q16_16(y), Q16_16(x)).0 as f64 * LSB;
+        let e15 = atan2_q15(Q1_15((y >> 16) as i16), Q1_15((x >> 16) as i16));
+        let t15 = ((y >> 16) as f64).atan2((x >> 16) as f64);
+        let e31 = atan2_q31(Q1_31(y), Q1_31(x)).0 as f64 * PI / 2f64.powi(31);
+
+        // both ends of the Q1.x ranges are the same angle
+        let wrap = |e: f64, t: f64| (e - t).abs().min((e - t + 2.0 * PI).abs());
+        (e - t).abs() < 2e-7 + LSB / 2.0
+            && wrap(e15.0 as f64 * PI / 32768.0, t15) < PI * (2e-7 + 0.5 / 32768.0)
+            && wrap(e31, t) < PI * 2e-7
+    }
+
+    #[test]
+    fn atan2_abs_err_qc() {
+        qc::quickcheck(check_atan2 as fn(i32, i32) -> bool)
+    }
+
+    #[test]
+    fn atan2_abs_err_exhaustive() {
+        for i in 0..1 << 16 {
+            let angle = i as f64 / (1 << 16) as f64 * 2.0 * PI;
+            for &r in &[1e3, 1e6, 2.1e9] {
+                let (y, x) = ((r * angle.sin()) as i32, (r * angle.cos()) as i32);
+                assert!(check_atan2(y, x), "{}, {}", y, x);
+            }
+        }
+    }
+
+    #[test]
+    fn atan2_edge_cases() {
+        let values = [i32::MIN, -2, -1, 0, 1, 2, i32::MAX];
+        for &x in &values {
+            for &y in &values {
+                assert!(check_atan2(y, x), "{}, {}", y, x);
+            }
+        }
+        let pi = Q16_16::from_f32(PI as f32);
+        assert_eq!(atan2(Q16_16(0), Q16_16(0)), Q16_16(0));
+        assert_eq!(atan2(Q16_16(0), Q16_16(1)), Q16_16(0));
+        assert_eq!(atan2(Q16_16(0), Q16_16(-1)), pi);
+        assert_eq!(atan2(Q16_16(1), Q16_16(0)), Q16_16::from_f32(PI as f32 / 2.0));
+        assert_eq!(atan2(Q16_16(-1), Q16_16(0)), Q16_16::from_f32(-PI as f32 / 2.0));
+        assert_eq!(atan2(Q16_16(i32::MIN), Q16_16(0)), Q16_16::from_f32(-PI as f32 / 2.0));
+
+        assert_eq!(atan2_q15(Q1_15(0), Q1_15(-1)), Q1_15::MIN);
+        assert_eq!(atan2_q15(Q1_15(1), Q1_15(0)), Q1_15(1 << 14));
+        assert_eq!(atan2_q15(Q1_15::MIN, Q1_15::MIN), Q1_15(-3 << 13));
+        assert_eq!(atan2_q31(Q1_31(0), Q1_31(-1)), Q1_31::MIN);
+        assert_eq!(atan2_q31(Q1_31(-1), Q1_31(0)), Q1_31(-1 << 30));
+    }
+
+    #[test]
+    fn atan2_binary_abs_err_qc() {
+        fn prop(y: i32, x: i32) -> bool {
+            let t = (y as f64).atan2(x as f64) / (2.0 * PI) * 2f64.powi(32);
+            let e = atan2_binary(y, x) as f64;
+            // compare modulo a full turn
+            let diff = (e - t).rem_euclid(2f64.powi(32));
+            diff.min(2f64.powi(32) - diff) < 107.0
+        }
+        qc::quickcheck(prop as fn(i32, i32) -> bool);
+        for &x in &[i32::MIN, -1, 0, 1, i32::MAX] {
+            for &y in &[i32::MIN, -1, 0, 1, i32::MAX] {
+                assert!(prop(y, x), "{}, {}", y, x);
+            }
+        }
+    }
+
+    #[test]
+    fn atan2_binary_edge_cases() {
+        assert_eq!(atan2_binary(0, 0), 0);
+        assert_eq!(atan2_binary(0, 1), 0);
+        assert_eq!(atan2_binary(1, 0), 1 << 30);
+        assert_eq!(atan2_binary(0, -1), 1 << 31);
+        assert_eq!(atan2_binary(-1, 0), 3 << 30);
+        assert_eq!(atan2_binary(i32::MIN, 0), 3 << 30);
+        assert_eq!(atan2_binary(0, i32::MIN), 1 << 31);
+    }
+}
@@ -10,11 +10,242 @@ pub fn flip_sign_nonnan(sign: f32, magnitude: f32) -> f32 {
     let (s2, e2, m2) = magnitude.decompose_raw();
     f32::recompose_raw(s1 ^ s2, e2, m2)
 }
+
+/// Split positive, finite `x` (including subnormals) exactly into
+/// `(k, m)`, with `x` = 2<sup>k</sup> m and 1/&radic;2 &le; m <
+/// &radic;2, so that m - 1 is exact.
+#[inline]
+pub fn split_near_one(x: f32) -> (i32, f32) {
+    use core::f32::consts::FRAC_1_SQRT_2;
+    const EXP2_23: f32 = 8388608.0;
+    let (x, k0) = if x < f32::MIN_POSITIVE { (x * EXP2_23, -23) } else { (x, 0) };
+    // subtracting the bits of 1/sqrt(2) puts the exponent of x,
+    // rounded to the nearest, in the exponent field
+    let bits = x.to_bits();
+    let k = (bits.wrapping_sub(FRAC_1_SQRT_2.to_bits()) as i32) >> SIGNIF;
+    let m = f32::from_bits(bits.wrapping_sub((k as u32) << SIGNIF));
+    (k + k0, m)
+}
+/// A fixed-size buffer for building a panic message in a `const fn`,
+/// where `format!` isn't available. Anything past the capacity is
+/// dropped.
+pub struct Message {
+    buf: [u8; 128],
+    len: usize,
+}
+
+impl Message {
+    pub const EMPTY: Message = Message { buf: [0; 128], len: 0 };
+
+    pub const fn push_str(self, s: &str) -> Message {
+        self.push_bytes(s.as_bytes())
+    }
+
+    const fn push_bytes(mut self, bytes: &[u8]) -> Message {
+        let mut i = 0;
+        while i < bytes.len() && self.len < self.buf.len() {
+            self.buf[self.len] = bytes[i];
+            self.len += 1;
+            i += 1;
+        }
+        self
+    }
+
+    /// Push the decimal digits of `n`, which has at most `count` of
+    /// them, with a `.` after the first if `point`.
+    const fn push_digits(mut self, n: u64, count: u32, point: bool) -> Message {
+        let mut i = count;
+        while i > 0 {
+            i -= 1;
+            let digit = (n / 10u64.pow(i) % 10) as u8;
+            self = self.push_bytes(&[b'0' + digit]);
+            if point && i + 1 == count && i > 0 {
+                self = self.push_bytes(b".");
+            }
+        }
+        self
+    }
+
+    /// Push `x` in the same format as `{:e}`: the shortest decimal
+    /// that rounds to `x`, in scientific notation.
+    pub const fn push_f32(mut self, x: f32) -> Message {
+        if x.is_nan() {
+            return self.push_str("NaN");
+        }
+        if x.is_sign_negative() {
+            self = self.push_str("-");
+        }
+        if x.abs() == f32::INFINITY {
+            return self.push_str("inf");
+        } else if x == 0.0 {
+            return self.push_str("0e0");
+        }
+
+        // a = v * 10^e, with 1 <= v < 10
+        let a = x.abs() as f64;
+        let mut e = 0;
+        let mut v = a;
+        while v >= 10.0 {
+            v /= 10.0;
+            e += 1;
+        }
+        while v < 1.0 {
+            v *= 10.0;
+            e -= 1;
+        }
+
+        // 9 significant digits always round trip, but fewer usually
+        // do too: use the fewest, and the closest with that many
+        // (rounding ties up, like `{:e}`).
+        let mut digits = 1;
+        let mut m;
+        let mut exp;
+        loop {
+            let k = digits as i32 - 1 - e;
+            let scaled = scale10(a, k);
+            m = scaled as u64;
+            let frac = scaled - m as f64;
+            if frac >= 0.5 {
+                m += 1;
+            }
+            exp = e;
+            if m == 10u64.pow(digits) {
+                // rounded up to the next power of ten
+                m /= 10;
+                exp += 1;
+            }
+            if digits == 9 || scale10(m as f64, exp - (digits as i32 - 1)) as f32 == x.abs() {
+                break;
+            }
+            digits += 1;
+        }
+        while digits > 1 && m % 10 == 0 {
+            m /= 10;
+            digits -= 1;
+        }
+
+        self = self.push_digits(m, digits, true).push_str("e");
+        if exp < 0 {
+            self = self.push_str("-");
+        }
+        let e = exp.unsigned_abs() as u64;
+        let count = if e >= 10 { 2 } else { 1 };
+        self.push_digits(e, count, false)
+    }
+
+    pub const fn as_str(&self) -> &str {
+        let (bytes, _) = self.buf.split_at(self.len);
+        match core::str::from_utf8(bytes) {
+            Ok(s) => s,
+            // only ASCII is pushed, except by `push_str`, and the
+            // capacity is never reached in practice
+            Err(_) => "(invalid message)",
+        }
+    }
+}
+
+/// `x * 10^k`, rounded once when |`k`| &le; 22 (so that the power of
+/// ten is exact).
+const fn scale10(x: f64, k: i32) -> f64 {
+    let mut p = 1.0;
+    let mut i = 0;
+    while i < k.unsigned_abs() {
+        p *= 10.0;
+        i += 1;
+    }
+    if k < 0 { x / p } else { x * p }
+}
+
+/// Panic with a message saying that the raw function `name` was
+/// called with `x` outside of `range`.
+#[cold]
+#[track_caller]
+pub const fn raw_domain_panic(name: &str, x: f32, range: &str) -> ! {
+    let message = Message::EMPTY
+        .push_str(name)
+        .push_str("(")
+        .push_f32(x)
+        .push_str(") is outside the allowed range ")
+        .push_str(range);
+    panic!("{}", message.as_str())
+}
+
+/// A raw function, values at the ends of its domain, and values just
+/// outside it.
+#[cfg(all(test, feature = "strict-raw"))]
+pub type RawDomain<'a> = (fn(f32) -> f32, &'a [f32], &'a [f32]);
+
+/// Asserts that the `check_raw!` in each function accepts every value
+/// inside its domain and panics on every value outside.
+#[cfg(all(test, feature = "strict-raw"))]
+pub fn assert_raw_domains(domains: &[RawDomain]) {
+    use std::panic::catch_unwind;
+    for &(f, inside, outside) in domains {
+        for &x in inside {
+            assert!(catch_unwind(|| f(x)).is_ok(), "panicked on {:e}", x);
+        }
+        for &x in outside {
+            assert!(catch_unwind(|| f(x)).is_err(), "didn't panic on {:e}", x);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quickcheck as qc;
+    use std::string::ToString;
     use std::f32 as f;
 
+    #[test]
+    fn push_f32_qc() {
+        fn prop(x: f32) -> bool {
+            Message::EMPTY.push_f32(x).as_str() == format!("{:e}", x)
+        }
+        qc::quickcheck(prop as fn(f32) -> bool)
+    }
+
+    #[test]
+    fn push_f32_exhaustive() {
+        // every 9973rd float, of both signs
+        let mut x = 0.0f32;
+        while x.is_finite() {
+            for &x in &[x, -x] {
+                assert_eq!(Message::EMPTY.push_f32(x).as_str(), format!("{:e}", x));
+            }
+            x = f32::from_bits(x.to_bits() + 9973);
+        }
+        for &x in &[f32::NAN, f32::INFINITY, f32::NEG_INFINITY, f32::MAX, f32::MIN,
+                    f32::MIN_POSITIVE, f32::from_bits(1), 0.1, 104.5, 1e10] {
+            assert_eq!(Message::EMPTY.push_f32(x).as_str(), format!("{:e}", x));
+        }
+    }
+
+    #[test]
+    fn message_truncates() {
+        let long = "x".repeat(200);
+        assert_eq!(Message::EMPTY.push_str(&long).as_str(), &long[..128]);
+        assert_eq!(Message::EMPTY.push_str("a").push_str("b").as_str(), "ab");
+        assert_eq!(Message::EMPTY.as_str(), "".to_string());
+    }
+
+    #[test]
+    fn split_near_one_exhaustive() {
+        let mut x = f32::from_bits(1);
+        while x.is_finite() {
+            let (k, m) = split_near_one(x);
+            assert!((f::consts::FRAC_1_SQRT_2..f::consts::SQRT_2).contains(&m), "{:e}: {}", x, m);
+            assert_eq!(m as f64 * 2f64.powi(k), x as f64, "{:e}", x);
+            x = f32::from_bits(x.to_bits() + 997);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "f(-1.5e0) is outside the allowed range [0, 1]")]
+    fn raw_domain_panic_message() {
+        raw_domain_panic("f", -1.5, "[0, 1]")
+    }
+
     #[test]
     fn test_flip_sign_nonnan() {
         assert_eq!(flip_sign_nonnan(2.0, 3.0), 3.0);
@@ -0,0 +1,231 @@
+use core::f32;
+use core::f32::consts::LN_2;
+use exp::exp_exact_anchors;
+use ieee754::Ieee754;
+use log::log2_accurate;
+
+/// From here up, the asymptotic (Stirling) series is accurate to
+/// `f32` precision; smaller arguments are shifted up to it with the
+/// recurrences for Γ(x + 1) and ψ(x + 1).
+const SHIFT: f32 = 8.0;
+
+/// ln x, for positive finite `x`.
+#[inline]
+fn ln(x: f32) -> f32 {
+    LN_2 * log2_accurate(x)
+}
+
+/// ln Γ(y) for finite `y >= SHIFT`.
+#[inline]
+fn lgamma_stirling(y: f32) -> f32 {
+    // ln √(2π) and the Bernoulli terms B_2k / (2k (2k - 1))
+    const HALF_LN_2PI: f32 = 0.918938533204672741780329736405617640;
+    const S1: f32 = 1.0 / 12.0;
+    const S2: f32 = -1.0 / 360.0;
+    const S3: f32 = 1.0 / 1260.0;
+    let r = 1.0 / y;
+    let r2 = r * r;
+    (y - 0.5) * ln(y) - y + HALF_LN_2PI + r * (S1 + r2 * (S2 + r2 * S3))
+}
+
+/// Compute a fast approximation of the logarithm of the gamma
+/// function, ln Γ(x), for positive `x`.
+///
+/// The maximum absolute error for `x` < 8 (where the result is at
+/// most 8.6) is less than 6e-5, and the maximum relative error above
+/// that is less than 1.5e-5.
+///
+/// `lgamma(±0)` and `lgamma(∞)` are &infin;, and the result overflows
+/// to &infin; for `x` above about 4e36. If `x` is negative (including
+/// the poles at the negative integers), or NaN, `lgamma` returns NaN.
+#[inline]
+pub fn lgamma(x: f32) -> f32 {
+    if x >= SHIFT {
+        if x < f32::INFINITY { lgamma_stirling(x) } else { x }
+    } else if x > 0.0 {
+        // ln Γ(x) = ln Γ(x + n) - ln (x (x + 1) ... (x + n - 1))
+        let mut y = x;
+        let mut prod = 1.0;
+        while y < SHIFT {
+            prod *= y;
+            y += 1.0;
+        }
+        lgamma_stirling(y) - ln(prod)
+    } else if x == 0.0 {
+        f32::INFINITY
+    } else {
+        // negative, or NaN
+        f32::NAN
+    }
+}
+
+/// Compute a fast approximation of the gamma function, Γ(x), for
+/// positive `x`.
+///
+/// This is `exp_exact_anchors(lgamma(x))`, and the maximum relative
+/// error is less than 0.0035. The result overflows to &infin; for `x`
+/// above about 35.04.
+///
+/// `gamma(±0)` is &plusmn;&infin; and `gamma(∞)` is &infin;. If `x` is
+/// negative (including the poles at the negative integers), or NaN,
+/// `gamma` returns NaN.
+#[inline]
+pub fn gamma(x: f32) -> f32 {
+    if x > 0.0 {
+        exp_exact_anchors(lgamma(x))
+    } else if x == 0.0 {
+        f32::INFINITY.copy_sign(x)
+    } else {
+        f32::NAN
+    }
+}
+
+/// Compute a fast approximation of the digamma function, ψ(x) =
+/// Γ'(x) / Γ(x), for positive `x`.
+///
+/// The maximum absolute error for `x` &ge; 0.25 is less than 3e-5,
+/// and below that, where ψ(x) &approx; -1/x, the maximum relative
+/// error is less than 1e-6 (the result is -&infin; for `x` below
+/// about 2.9e-39, where ψ(x) < -`f32::MAX`).
+///
+/// `digamma(±0)` is &#8723;&infin; and `digamma(∞)` is &infin;. If `x`
+/// is negative (including the poles at the negative integers), or
+/// NaN, `digamma` returns NaN.
+#[inline]
+pub fn digamma(x: f32) -> f32 {
+    // the Bernoulli terms B_2k / 2k
+    const P1: f32 = -1.0 / 12.0;
+    const P2: f32 = 1.0 / 120.0;
+    const P3: f32 = -1.0 / 252.0;
+    if x < 0.0 || x.is_nan() {
+        return f32::NAN
+    }
+    if x == f32::INFINITY {
+        return x
+    }
+    // ψ(x) = ψ(x + n) - (1/x + 1/(x + 1) + ... + 1/(x + n - 1))
+    let mut y = x;
+    let mut sum = 0.0;
+    while y < SHIFT {
+        sum += 1.0 / y;
+        y += 1.0;
+    }
+    let r = 1.0 / y;
+    let r2 = r * r;
+    ln(y) - 0.5 * r + r2 * (P1 + r2 * (P2 + r2 * P3)) - sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{f32, f64};
+    use std::f64::consts::PI;
+
+    /// (y, ln |x (x + 1) ... (y - 1)|, 1/x + ... + 1/(y - 1)) with
+    /// y = x + n >= 20.
+    fn exact_shift(x: f64) -> (f64, f64, f64) {
+        let mut y = x;
+        let mut ln_prod = 0.0;
+        let mut sum = 0.0;
+        while y < 20.0 {
+            ln_prod += y.ln();
+            sum += 1.0 / y;
+            y += 1.0;
+        }
+        (y, ln_prod, sum)
+    }
+
+    fn exact_lgamma(x: f64) -> f64 {
+        let (y, ln_prod, _) = exact_shift(x);
+        let r = 1.0 / y;
+        let r2 = r * r;
+        let series = r * (1.0 / 12.0 + r2 * (-1.0 / 360.0 + r2 * (1.0 / 1260.0 + r2 * (-1.0 / 1680.0))));
+        (y - 0.5) * y.ln() - y + 0.5 * (2.0 * PI).ln() + series - ln_prod
+    }
+
+    fn exact_digamma(x: f64) -> f64 {
+        let (y, _, sum) = exact_shift(x);
+        let r2 = 1.0 / (y * y);
+        let series = r2 * (-1.0 / 12.0 + r2 * (1.0 / 120.0 + r2 * (-1.0 / 252.0 + r2 / 240.0)));
+        y.ln() - 0.5 / y + series - sum
+    }
+
+    /// Every 127th f32 from the smallest subnormal to `max`.
+    fn inputs(max: f32) -> impl Iterator<Item = f32> {
+        (1..max.to_bits()).step_by(127).map(f32::from_bits)
+    }
+
+    #[test]
+    fn lgamma_err() {
+        let mut max = (0.0f64, 0.0f64);
+        for x in inputs(1e36) {
+            let t = exact_lgamma(x as f64);
+            let e = lgamma(x) as f64;
+            let err = (e - t).abs();
+            if x < 8.0 {
+                max.0 = max.0.max(err);
+                assert!(err < 6e-5, "{:e}: {:e} {:e}", x, e, t);
+            } else {
+                max.1 = max.1.max(err / t);
+                assert!(err < 1.5e-5 * t, "{:e}: {:e} {:e}", x, e, t);
+            }
+        }
+        println!("maximum {:e}, relative {:e}", max.0, max.1);
+    }
+
+    #[test]
+    fn gamma_rel_err() {
+        let mut max = 0.0f64;
+        for x in inputs(35.0) {
+            let t = exact_lgamma(x as f64).exp();
+            if t > f32::MAX as f64 { continue }
+            let e = gamma(x) as f64;
+            let rel = (e - t).abs() / t;
+            max = max.max(rel);
+            assert!(rel < 0.0035, "{:e}: {:e} {:e}", x, e, t);
+        }
+        println!("maximum {:e}", max);
+    }
+
+    #[test]
+    fn digamma_err() {
+        let mut max = (0.0f64, 0.0f64);
+        for x in inputs(f32::MAX) {
+            let t = exact_digamma(x as f64);
+            let e = digamma(x) as f64;
+            let err = (e - t).abs();
+            if t < f32::MIN as f64 {
+                assert_eq!(e, f64::NEG_INFINITY, "{:e}", x);
+            } else if x >= 0.25 {
+                max.0 = max.0.max(err);
+                assert!(err < 3e-5, "{:e}: {:e} {:e}", x, e, t);
+            } else {
+                max.1 = max.1.max(err / t.abs());
+                assert!(err < 1e-6 * t.abs(), "{:e}: {:e} {:e}", x, e, t);
+            }
+        }
+        println!("maximum {:e}, relative {:e}", max.0, max.1);
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert_eq!(lgamma(0.0), f32::INFINITY);
+        assert_eq!(lgamma(-0.0), f32::INFINITY);
+        assert_eq!(lgamma(f32::INFINITY), f32::INFINITY);
+        assert_eq!(lgamma(f32::MAX), f32::INFINITY);
+        assert_eq!(gamma(0.0), f32::INFINITY);
+        assert_eq!(gamma(-0.0), f32::NEG_INFINITY);
+        assert_eq!(gamma(f32::INFINITY), f32::INFINITY);
+        assert_eq!(gamma(36.0), f32::INFINITY);
+        assert_eq!(digamma(0.0), f32::NEG_INFINITY);
+        assert_eq!(digamma(-0.0), f32::INFINITY);
+        assert_eq!(digamma(f32::INFINITY), f32::INFINITY);
+        assert!(digamma(f32::MAX).is_finite());
+
+        for &x in &[f32::NAN, -1.0, -2.0, -0.5, -1e-40, f32::NEG_INFINITY] {
+            assert!(lgamma(x).is_nan(), "{}", x);
+            assert!(gamma(x).is_nan(), "{}", x);
+            assert!(digamma(x).is_nan(), "{}", x);
+        }
+    }
+}
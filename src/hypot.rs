@@ -0,0 +1,167 @@
+use core::f32;
+use sqrt::sqrt_raw;
+
+/// How accurately `hypot_with` computes its result, trading accuracy
+/// for speed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HypotAccuracy {
+    /// "Alpha max plus beta min": a weighted sum of the larger and
+    /// smaller magnitudes, with no division or square root. The
+    /// maximum relative error is less than 0.04.
+    Coarse,
+    /// A polynomial in the ratio of the magnitudes, with one division
+    /// and no square root. The maximum relative error is less than
+    /// 1.3e-4.
+    Medium,
+    /// The behaviour of `hypot`, with one division and a square root.
+    /// The maximum relative error is less than 3e-7.
+    Full,
+}
+
+/// The larger and smaller of `|x|` and `|y|`, or, if the result is
+/// determined without any arithmetic (zeros, infinities and NaNs),
+/// that result.
+#[inline]
+fn order(x: f32, y: f32) -> Result<(f32, f32), f32> {
+    let (x, y) = (x.abs(), y.abs());
+    let (hi, lo) = if x >= y { (x, y) } else { (y, x) };
+    if hi == f32::INFINITY || lo == f32::INFINITY {
+        // even if the other is NaN
+        Err(f32::INFINITY)
+    } else if lo == 0.0 || hi.is_nan() || lo.is_nan() {
+        // covers x = y = 0, where the ratio would be NaN
+        Err(hi + lo)
+    } else {
+        Ok((hi, lo))
+    }
+}
+
+/// √(1 + t<sup>2</sup>) for 0 &le; `t` &le; 1, the scale from the
+/// larger magnitude to the hypotenuse.
+#[inline]
+pub(crate) fn hypot_scale(t: f32) -> f32 {
+    sqrt_raw(1.0 + t * t)
+}
+
+/// Compute a fast approximation of √(x<sup>2</sup> +
+/// y<sup>2</sup>), the length of the hypotenuse of a right-angled
+/// triangle with sides `x` and `y`.
+///
+/// There is no overflow or underflow in the intermediate results, so
+/// the result is finite whenever the exact one is, and the maximum
+/// relative error across all f32s is less than 3e-7.
+///
+/// If either of `x` and `y` is infinite, `hypot` returns &infin;,
+/// even if the other is NaN. Otherwise, if either is NaN, `hypot`
+/// returns NaN.
+///
+/// See also `hypot_with` for faster, less accurate versions.
+#[inline]
+pub fn hypot(x: f32, y: f32) -> f32 {
+    match order(x, y) {
+        Ok((hi, lo)) => hi * hypot_scale(lo / hi),
+        Err(r) => r,
+    }
+}
+
+/// Compute a fast approximation of √(x<sup>2</sup> +
+/// y<sup>2</sup>), with the accuracy (and speed) chosen by
+/// `accuracy`.
+///
+/// Like `hypot`, there is no overflow or underflow in the
+/// intermediate results for any of the choices (although a result
+/// within the relative error of `f32::MAX` may round up to &infin;),
+/// and infinities and NaNs are handled the same way.
+#[inline]
+#[allow(clippy::excessive_precision)]
+pub fn hypot_with(x: f32, y: f32, accuracy: HypotAccuracy) -> f32 {
+    // α = 2 cos(π/8) / (1 + cos(π/8)) and β = 2 sin(π/8) / (1 +
+    // cos(π/8)) balance the errors at the ends and middle of the
+    // octant
+    const ALPHA: f32 = 0.960433870103419;
+    const BETA: f32 = 0.397824734759316;
+    let (hi, lo) = match order(x, y) {
+        Ok(pair) => pair,
+        Err(r) => return r,
+    };
+    match accuracy {
+        HypotAccuracy::Coarse => ALPHA * hi + BETA * lo,
+        HypotAccuracy::Medium => {
+            // minimax approximation of √(1 + t^2) on [0, 1]
+            const C0: f32 = 1.00011909557;
+            const C1: f32 = -0.00600584426774;
+            const C2: f32 = 0.547204607155;
+            const C3: f32 = -0.126935869513;
+            let t = lo / hi;
+            hi * (C0 + t * (C1 + t * (C2 + t * C3)))
+        }
+        HypotAccuracy::Full => hi * hypot_scale(lo / hi),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+    use std::f32;
+
+    const MODES: &[(HypotAccuracy, f64)] = &[
+        (HypotAccuracy::Coarse, 0.04),
+        (HypotAccuracy::Medium, 1.3e-4),
+        (HypotAccuracy::Full, 3e-7),
+    ];
+
+    #[test]
+    fn rel_err_qc() {
+        fn prop(x: f32, y: f32) -> qc::TestResult {
+            if !x.is_finite() || !y.is_finite() { return qc::TestResult::discard() }
+            let t = (x as f64).hypot(y as f64);
+            let ok = MODES.iter().all(|&(mode, tol)| {
+                (hypot_with(x, y, mode) as f64 - t).abs() <= tol * t
+            });
+            qc::TestResult::from_bool(ok && hypot(x, y) == hypot_with(x, y, HypotAccuracy::Full))
+        }
+        qc::quickcheck(prop as fn(f32, f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn rel_err_exhaustive() {
+        // every 127th ratio, at scales where the squares overflow or
+        // underflow
+        let mut max = [0.0f64; 3];
+        let mut t = 0.0f32;
+        while t <= 1.0 {
+            for &scale in &[1.0, 1e-30, 1e38] {
+                let (x, y) = (scale, t * scale);
+                let exact = (x as f64).hypot(y as f64);
+                for (&(mode, tol), max) in MODES.iter().zip(&mut max) {
+                    let e = hypot_with(x, y, mode) as f64;
+                    let rel = (e - exact).abs() / exact;
+                    if rel > *max { *max = rel }
+                    assert!(rel <= tol, "{:?} {:e} {:e}: {:e}", mode, x, y, rel);
+                }
+            }
+            t = f32::from_bits(t.to_bits() + 127);
+        }
+        println!("maximum {:?}", max);
+    }
+
+    #[test]
+    fn edge_cases() {
+        let inf = f32::INFINITY;
+        let nan = f32::NAN;
+        for &mode in &[HypotAccuracy::Coarse, HypotAccuracy::Medium, HypotAccuracy::Full] {
+            assert_eq!(hypot_with(0.0, -0.0, mode), 0.0);
+            assert_eq!(hypot_with(-3.0, 0.0, mode), 3.0);
+            assert_eq!(hypot_with(0.0, 1e-45, mode), 1e-45);
+            assert!(hypot_with(1e-45, 1e-45, mode) > 0.0);
+            assert_eq!(hypot_with(nan, -inf, mode), inf);
+            assert_eq!(hypot_with(inf, nan, mode), inf);
+            assert!(hypot_with(nan, 1.0, mode).is_nan());
+            assert!(hypot_with(f32::MAX, f32::MAX, mode).is_infinite());
+            assert!(hypot_with(1e38, 1e38, mode).is_finite());
+        }
+        assert_eq!(hypot(f32::MAX, 1.0), f32::MAX);
+        assert_eq!(hypot(3.0, 4.0), 5.0);
+    }
+}
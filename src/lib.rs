@@ -23,19 +23,73 @@
 //! // they should be close
 //! assert!((approx - real).abs() < 0.01);
 //! ```
+//!
+//! `exp2_raw`, `exp_raw`, `log2_raw` and `atan_raw` are `const fn`s,
+//! and give bit-for-bit the same results whether evaluated at compile
+//! time or at run time, so they can be used to build tables in
+//! `const`s and `static`s.
 
 #![no_std]
 #[cfg(test)] extern crate quickcheck;
 #[cfg(test)] #[macro_use] extern crate std;
 extern crate ieee754;
 
-pub use log::{log2, log2_raw};
-pub use atan::{atan_raw, atan, atan2};
-pub use exp::{exp_raw, exp2_raw, exp, exp2};
+pub use log::{log2, log2_raw, log2_1p, log1p};
+pub use atan::{atan_raw, atan, atan2, atan2pi, atan2_turns, atan2_deg, atan2_positive,
+               atan2_i32,
+               pseudo_angle, pseudo_angle_to_radians, radians_to_pseudo_angle,
+               to_polar, from_polar};
+pub use exp::{exp_raw, exp2_raw, exp, exp2, expm1, exp_exact_anchors, exp2_exact_anchors,
+              exp_with_subnormals, exp2_with_subnormals, Subnormals,
+              exp_sat, exp2_sat, exp_clamped};
+pub use asin::{asin_raw, asin, acos_raw, acos};
+pub use asinh::{asinh, acosh, atanh};
+pub use tan::{tan_raw, tan};
+pub use sinpi::{sinpi, cospi, sin_turns, cos_turns};
+pub use erf::{erf_raw, erf, erfc_raw, erfc, erf_inv_raw, erf_inv};
+pub use gamma::{lgamma, gamma, digamma};
+pub use softmax::{logsumexp, softmax, log_softmax};
+pub use hypot::{hypot, hypot_with, HypotAccuracy};
+
+/// With the `strict-raw` feature, panic unless `$ok`, naming the raw
+/// function `$name`, its input `$x` and its allowed range `$range`.
+macro_rules! check_raw {
+    ($ok: expr, $name: expr, $x: expr, $range: expr) => {
+        // a NaN input must fail the check, so `$ok` is written to be
+        // false for NaN, and then negated
+        let ok: bool = $ok;
+        if cfg!(feature = "strict-raw") && !ok {
+            ::float::raw_domain_panic($name, $x, $range)
+        }
+    }
+}
 
 mod log;
 mod atan;
 mod exp;
+mod asin;
+mod asinh;
+mod tan;
+mod sinpi;
+mod erf;
+mod gamma;
+mod softmax;
+mod hypot;
+mod sqrt;
+
+pub mod fixed;
+pub mod angle;
+pub mod checked;
+pub mod stats;
+pub mod complex;
+pub mod color;
+pub mod audio;
+
+#[cfg(feature = "tables")]
+pub mod tables;
 
 #[doc(hidden)]
 pub mod float;
+
+#[cfg(test)]
+mod reference;
@@ -1,11 +1,15 @@
 use core::f32 as f;
+use core::f32::consts::{LN_2, FRAC_1_SQRT_2, SQRT_2};
+use float;
 use ieee754::Ieee754;
 
 /// Compute a fast approximation of the base-2 logarithm of `x`.
 ///
 /// The maximum relative error across all positive f32s (including
 /// denormals) is less than 0.022. The maximum absolute error is less
-/// than 0.009.
+/// than 0.009. At every power of two (including denormals), the
+/// result is exact: `log2(2^k) == k`. `log2` is monotone: `log2(x)
+/// <= log2(y)` whenever `x <= y`.
 ///
 /// If `x` is negative, or NaN, `log2` returns `NaN`.
 ///
@@ -55,7 +59,8 @@ fn log2_exp_0(signif: u32) -> f32 {
 /// required (at the expense of some speed).
 ///
 /// The maximum relative error across all valid input is less than
-/// 0.022. The maximum absolute error is less than 0.009.
+/// 0.022. The maximum absolute error is less than 0.009. At every
+/// power of two, the result is exact, and it is monotone.
 ///
 /// |               | Time (ns) |
 /// |--------------:|----------------|
@@ -63,18 +68,119 @@ fn log2_exp_0(signif: u32) -> f32 {
 /// |     `log2(x)` | 4.0            |
 /// | `log2_raw(x)` | 2.7            |
 #[inline]
-pub fn log2_raw(x: f32) -> f32 {
-    let (_sign, exp, signif) = x.decompose_raw();
-    debug_assert!(!_sign && 1 <= exp && exp <= 254);
+pub const fn log2_raw(x: f32) -> f32 {
+    // `decompose_raw`/`recompose_raw` by hand, since they can't be
+    // called in a `const fn`.
+    let bits = x.to_bits();
+    let exp = ((bits >> float::SIGNIF) & 0xFF) as u8;
+    let signif = bits & ((1 << float::SIGNIF) - 1);
+    check_raw!(x >= f32::MIN_POSITIVE && x <= f32::MAX, "log2_raw", x,
+               "[1.1754944e-38, 3.4028235e38]");
+    debug_assert!(bits >> 31 == 0 && exp != 0 && exp != 0xFF);
 
     let high_bit = ((signif >> 22) & 1) as u8;
     let add_exp = (exp + high_bit) as i32 - 127;
-    let normalised = f32::recompose_raw(false, 0x7F ^ high_bit, signif) - 1.0;
+    let normalised = f32::from_bits((((0x7F ^ high_bit) as u32) << float::SIGNIF) | signif) - 1.0;
     const A: f32 = -0.6296735;
     const B: f32 = 1.466967;
     add_exp as f32 + normalised * (B + A * normalised)
 }
 
+/// Compute a fast approximation of the base-2 logarithm of `1 + x`.
+///
+/// Unlike `log2(1.0 + x)`, this keeps its relative accuracy for `x`
+/// near zero, where `1 + x` loses the low bits of `x`: the maximum
+/// relative error for 1/&radic;2 - 1 < `x` < &radic;2 - 1 (about -0.29
+/// < `x` < 0.41) is less than 6e-5, and `log2_1p(0.0)` is exactly
+/// 0. Elsewhere, this is `log2(1.0 + x)`, with the same error as
+/// `log2`.
+///
+/// If `x` is less than -1, or NaN, `log2_1p` returns NaN.
+#[inline]
+pub fn log2_1p(x: f32) -> f32 {
+    if FRAC_1_SQRT_2 - 1.0 < x && x < SQRT_2 - 1.0 {
+        log2_1p_kernel(x)
+    } else {
+        // NaN ends up here, and propagates
+        log2(1.0 + x)
+    }
+}
+
+/// log2(1 + x) for 1/&radic;2 - 1 &le; `x` &le; &radic;2 - 1, with relative
+/// error 6e-5.
+#[inline]
+#[allow(clippy::excessive_precision)]
+pub(crate) fn log2_1p_kernel(x: f32) -> f32 {
+    const C0: f32 = 1.442646251;
+    const C1: f32 = -0.7205549723;
+    const C2: f32 = 0.4853065147;
+    const C3: f32 = -0.3908924424;
+    const C4: f32 = 0.2547518727;
+    x * (C0 + x * (C1 + x * (C2 + x * (C3 + x * C4))))
+}
+
+/// Compute the base-2 logarithm of **positive, finite** `x`
+/// (including denormals), more accurately than `log2`.
+///
+/// This reduces `x` exactly to 2<sup>k</sup> m, with 1/&radic;2 &le; m
+/// < &radic;2, and then uses the `log2_1p` polynomial on m - 1. The
+/// maximum absolute error is less than 3.5e-5, and the relative error
+/// near 1 is the same as `log2_1p`. It is used internally by
+/// functions that are sensitive to errors in a logarithm.
+#[inline]
+pub(crate) fn log2_accurate(x: f32) -> f32 {
+    let (k, m) = float::split_near_one(x);
+    k as f32 + log2_1p_kernel(m - 1.0)
+}
+
+/// Compute the base-2 logarithm of `x`, more accurately again than
+/// `log2_accurate`.
+///
+/// This uses the same reduction, but then a polynomial in s = (m -
+/// 1)/(m + 1), which converges much faster than one in m - 1. The
+/// maximum absolute error is less than 1.2e-7 for 1/&radic;2 &le; `x`
+/// < &radic;2, and less than 7.7e-6 elsewhere, where it is dominated
+/// by rounding the result. It
+/// is used internally by functions whose errors are visible in their
+/// results, like `color` and `audio`.
+///
+/// `log2_precise(0.0)` is -&infin; and `log2_precise(∞)` is &infin;.
+/// If `x` is negative, or NaN, `log2_precise` returns NaN.
+#[inline]
+#[allow(clippy::excessive_precision)]
+pub(crate) fn log2_precise(x: f32) -> f32 {
+    // log2((1 + s) / (1 - s)) / s as a polynomial in s^2, for |s| <=
+    // (√2 - 1) / (√2 + 1)
+    const C0: f32 = 2.885391289368934786529876156491245999828207;
+    const C1: f32 = 0.9614708089526840584302874324066864106692365;
+    const C2: f32 = 0.5989738857191477260718778498059000782329028;
+    if x > 0.0 && x < f::INFINITY {
+        let (k, m) = float::split_near_one(x);
+        let s = (m - 1.0) / (m + 1.0);
+        let s2 = s * s;
+        k as f32 + s * (C0 + s2 * (C1 + s2 * C2))
+    } else if x == 0.0 {
+        f::NEG_INFINITY
+    } else if x == f::INFINITY {
+        x
+    } else {
+        // negative, or NaN
+        f::NAN
+    }
+}
+
+/// Compute a fast approximation of the natural logarithm of `1 + x`.
+///
+/// This is `log2_1p(x)` scaled by ln 2, with the same guarantees:
+/// the maximum relative error for about -0.29 < `x` < 0.41 is less
+/// than 6e-5, and elsewhere it is the same as `log2`.
+///
+/// If `x` is less than -1, or NaN, `log1p` returns NaN.
+#[inline]
+pub fn log1p(x: f32) -> f32 {
+    LN_2 * log2_1p(x)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,6 +228,65 @@ mod tests {
         assert_eq!(log2(f32::recompose_raw(false, 0, 1)), -149.0);
     }
 
+    #[test]
+    fn exact_at_powers_of_two() {
+        for k in -149..128 {
+            let x = 2f64.powi(k) as f32;
+            assert_eq!(log2(x), k as f32, "{}", k);
+            if k >= -126 {
+                assert_eq!(log2_raw(x), k as f32, "{}", k);
+            }
+        }
+        assert_eq!(log2(1.0).to_bits(), 0.0f32.to_bits());
+    }
+
+    #[test]
+    fn monotone() {
+        // every float in ranges covering several segment boundaries,
+        // the switch to denormals, and the ends of the domain of
+        // `log2_raw`
+        let full = &[log2 as fn(f32) -> f32];
+        let raw = &[log2_raw as fn(f32) -> f32];
+        for &(lo, hi, fs) in &[(0.5, 4.0, &full[..]),
+                               (f32::MIN_POSITIVE / 4.0, f32::MIN_POSITIVE * 4.0, &full[..]),
+                               (0.5, 4.0, &raw[..]),
+                               (f32::MIN_POSITIVE, f32::MIN_POSITIVE * 4.0, &raw[..]),
+                               (f32::MAX / 4.0, f32::MAX, &raw[..])] {
+            for &f in fs {
+                let mut prev = f(lo);
+                for x in lo.next().upto(hi) {
+                    let e = f(x);
+                    assert!(e >= prev, "{:e}: {:e} < {:e}", x, e, prev);
+                    prev = e;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn log2_raw_const() {
+        const N: usize = 1 << 12;
+        const fn input(i: usize) -> f32 {
+            // spread across all positive, finite, normal floats
+            const LO: u32 = 0x0080_0000;
+            const HI: u32 = 0x7F7F_FFFF;
+            f32::from_bits(LO + (i as u32) * ((HI - LO) / N as u32))
+        }
+        static TABLE: [f32; N] = {
+            let mut table = [0.0; N];
+            let mut i = 0;
+            while i < N {
+                table[i] = log2_raw(input(i));
+                i += 1;
+            }
+            table
+        };
+        for (i, &e) in TABLE.iter().enumerate() {
+            let x = input(i);
+            assert_eq!(e.to_bits(), log2_raw(x).to_bits(), "{}", x);
+        }
+    }
+
     #[test]
     fn denormals() {
         fn prop(x: u8, y: u16) -> bool {
@@ -143,4 +308,103 @@ mod tests {
         }
         qc::quickcheck(prop as fn(u8, u16) -> bool)
     }
+
+    #[test]
+    fn log2_accurate_abs_err() {
+        let mut x = f32::from_bits(1);
+        while x.is_finite() {
+            let e = log2_accurate(x) as f64;
+            let t = (x as f64).log2();
+            assert!((e - t).abs() < 3.5e-5, "{:e}: {} {}", x, e, t);
+            if (0.5..2.0).contains(&x) && x != 1.0 {
+                assert!(((e - t) / t).abs() < 6e-5, "{:e}: {} {}", x, e, t);
+            }
+            x = f32::from_bits(x.to_bits() + 997);
+        }
+        assert_eq!(log2_accurate(1.0), 0.0);
+        for k in -149..128 {
+            let x = if k < -126 { f32::from_bits(1 << (k + 149)) } else { 2f32.powi(k) };
+            assert_eq!(log2_accurate(x), k as f32, "{}", k);
+        }
+    }
+
+    #[test]
+    fn log2_precise_abs_err() {
+        let mut x = f32::from_bits(1);
+        while x.is_finite() {
+            let e = log2_precise(x) as f64;
+            let t = (x as f64).log2();
+            let tol = if (FRAC_1_SQRT_2..SQRT_2).contains(&x) { 1.2e-7 } else { 7.7e-6 };
+            assert!((e - t).abs() < tol, "{:e}: {} {}", x, e, t);
+            x = f32::from_bits(x.to_bits() + 97);
+        }
+        for k in -149..128 {
+            let x = if k < -126 { f32::from_bits(1 << (k + 149)) } else { 2f32.powi(k) };
+            assert_eq!(log2_precise(x), k as f32, "{}", k);
+        }
+        assert_eq!(log2_precise(0.0), f::NEG_INFINITY);
+        assert_eq!(log2_precise(f::INFINITY), f::INFINITY);
+        assert!(log2_precise(-1.0).is_nan());
+        assert!(log2_precise(f::NAN).is_nan());
+    }
+
+    #[test]
+    fn log1p_rel_err_exhaustive() {
+        let mut max = 0.0f64;
+        for i in 0..PREC + 1 {
+            for j in -12..4 {
+                for &sign in &[-1.0, 1.0] {
+                    let x = sign * (1.0 + i as f32 / PREC as f32) * 2f32.powi(j * 2);
+                    if x < -1.0 { continue }
+                    let t = (x as f64).ln_1p();
+                    let small = FRAC_1_SQRT_2 - 1.0 < x && x < SQRT_2 - 1.0;
+                    let tol = if small { 6e-5 } else { 0.025 };
+                    for &(e, t) in &[(log1p(x) as f64, t),
+                                     (log2_1p(x) as f64, t * core::f64::consts::LOG2_E)] {
+                        let rel = ((e - t) / t).abs();
+                        if small && rel > max { max = rel }
+                        // equality handles log1p(-1) == -inf
+                        assert!(rel < tol || e == t,
+                                "{:.8}: e = {:.8e}, t = {:.8e}. {:.4e}", x, e, t, rel);
+                    }
+                }
+            }
+        }
+        println!("maximum {}", max);
+    }
+
+    #[test]
+    fn log1p_tiny() {
+        let mut x = f32::MIN_POSITIVE;
+        while x < 0.25 {
+            for &x in &[x, -x] {
+                let t = (x as f64).ln_1p();
+                let rel = ((log1p(x) as f64 - t) / t).abs();
+                assert!(rel < 6e-5, "{:e}: {:e}", x, rel);
+            }
+            x = f32::from_bits(x.to_bits() + 9973);
+        }
+    }
+
+    #[test]
+    fn log1p_edge_cases() {
+        for &f in &[log1p as fn(f32) -> f32, log2_1p] {
+            assert!(f(f32::NAN).is_nan());
+            assert!(f(-1.5).is_nan());
+            assert!(f(f32::NEG_INFINITY).is_nan());
+            assert_eq!(f(-1.0), f32::NEG_INFINITY);
+            assert_eq!(f(f32::INFINITY), f32::INFINITY);
+            assert_eq!(f(0.0).to_bits(), 0.0f32.to_bits());
+            assert_eq!(f(-0.0).to_bits(), (-0.0f32).to_bits());
+        }
+        assert_eq!(log2_1p(1.0), 1.0);
+    }
+
+    #[test]
+    #[cfg(feature = "strict-raw")]
+    fn raw_strict_domain() {
+        ::float::assert_raw_domains(&[
+            (log2_raw, &[f32::MIN_POSITIVE, f32::MAX], &[0.0, -2.0, f32::INFINITY, f32::NAN]),
+        ]);
+    }
 }
@@ -0,0 +1,64 @@
+//! Reference implementations, computed in `f64`, that tests in
+//! several modules compare against.
+
+use std::f64::consts::PI;
+
+/// erfc(x) to close to f64 precision.
+pub fn exact_erfc(x: f64) -> f64 {
+    if x < 0.0 {
+        2.0 - exact_erfc(-x)
+    } else if x < 3.0 {
+        // the Taylor series of erf, which loses a few digits to
+        // cancellation for x near 3
+        let s = x * x;
+        let mut term = x;
+        let mut sum = x;
+        let mut n = 0.0;
+        while term.abs() > 1e-18 {
+            n += 1.0;
+            term *= -s / n;
+            sum += term / (2.0 * n + 1.0);
+        }
+        1.0 - 2.0 / PI.sqrt() * sum
+    } else {
+        // continued fraction, evaluated backwards
+        let mut f = x;
+        for k in (1..200).rev() {
+            f = x + (k as f64 / 2.0) / f;
+        }
+        (-x * x).exp() / PI.sqrt() / f
+    }
+}
+
+/// erf(x) to close to f64 precision.
+pub fn exact_erf(x: f64) -> f64 {
+    if x.abs() < 0.5 {
+        // avoid cancellation
+        let s = x * x;
+        let mut term = x;
+        let mut sum = x;
+        let mut n = 0.0;
+        while term.abs() > 1e-25 {
+            n += 1.0;
+            term *= -s / n;
+            sum += term / (2.0 * n + 1.0);
+        }
+        2.0 / PI.sqrt() * sum
+    } else {
+        (1.0 - exact_erfc(x.abs())).copysign(x)
+    }
+}
+
+/// erf<sup>-1</sup>(x), by Newton's method starting at `guess`,
+/// on erfc for `x` near 1, to avoid cancellation.
+pub fn exact_erf_inv(x: f32, guess: f32) -> f64 {
+    let a = (x as f64).abs();
+    let mut y = (guess as f64).abs();
+    for _ in 0..50 {
+        let diff = if a < 0.5 { a - exact_erf(y) } else { exact_erfc(y) - (1.0 - a) };
+        let step = diff / (2.0 / PI.sqrt() * (-y * y).exp());
+        y += step;
+        if step.abs() <= 1e-15 * y { break }
+    }
+    y.copysign(x as f64)
+}
@@ -0,0 +1,323 @@
+use core::f32;
+use ieee754::Ieee754;
+use core::f32::consts::FRAC_1_PI;
+use tan::{self, REDUCE_LIMIT};
+
+/// 2^23: every f32 at least this large is an integer.
+const INTEGRAL: f32 = 8388608.0;
+
+/// sin(&pi; r) for `|r| <= 1/4`, with relative error 1.6e-6.
+#[inline]
+#[allow(clippy::excessive_precision)]
+fn sin_kernel(r: f32) -> f32 {
+    const S0: f32 = 3.14158791886;
+    const S1: f32 = -5.16638436026;
+    const S2: f32 = 2.49407773841;
+    let r2 = r * r;
+    r * (S0 + r2 * (S1 + r2 * S2))
+}
+
+/// cos(&pi; r) for `|r| <= 1/4`, with relative error 3.9e-8.
+#[inline]
+#[allow(clippy::excessive_precision)]
+fn cos_kernel(r: f32) -> f32 {
+    const C0: f32 = -4.93479082542;
+    const C1: f32 = 4.05765137795;
+    const C2: f32 = -1.30670611252;
+    let r2 = r * r;
+    1.0 + r2 * (C0 + r2 * (C1 + r2 * C2))
+}
+
+/// Reduce `x` (in half turns) exactly, to `r` in [-1/4, 1/4] and the
+/// quarter turn `n`, with `x = r + n / 2`.
+#[inline]
+fn reduce(x: f32) -> (f32, i32) {
+    if x.abs() < INTEGRAL {
+        let mut n = (x + x) as i32;
+        // exact, as both are multiples of ulp(x) and close together
+        let mut r = x - n as f32 * 0.5;
+        if r > 0.25 {
+            n += 1;
+            r -= 0.5;
+        } else if r < -0.25 {
+            n -= 1;
+            r += 0.5;
+        }
+        (r, n)
+    } else {
+        // x is an integer, or infinite or NaN (in which case r is
+        // NaN, which propagates through the kernels)
+        let n = if x.abs() < 2.0 * INTEGRAL { x as i32 * 2 } else { 0 };
+        (x * 0.0, n)
+    }
+}
+
+/// The fractional part of `t`, with the sign of `t`, or NaN if `t`
+/// is infinite or NaN.
+#[inline]
+fn fract(t: f32) -> f32 {
+    if t.abs() < INTEGRAL {
+        // the subtraction gives +0 for every integer
+        (t - t as i32 as f32).copy_sign(t)
+    } else {
+        t * 0.0
+    }
+}
+
+/// Compute a fast approximation of `sin(PI * x)`.
+///
+/// The reduction of `x` modulo 2 is exact, so there's no error from
+/// multiplying by an approximation of &pi;: `sinpi` is exactly 0 at
+/// every integer, with the sign of `x` like IEEE 754 `sinPi`, and
+/// exactly &plusmn;1 at every half-integer. The maximum relative
+/// error across all f32s is less than 1.7e-6.
+///
+/// If `x` is NaN or infinite, `sinpi` returns NaN.
+#[inline]
+pub fn sinpi(x: f32) -> f32 {
+    let (r, n) = reduce(x);
+    let s = match n & 3 {
+        0 => sin_kernel(r),
+        1 => cos_kernel(r),
+        2 => -sin_kernel(r),
+        _ => -cos_kernel(r),
+    };
+    // the reduction loses the sign of integer `x`
+    if s == 0.0 { s.copy_sign(x) } else { s }
+}
+
+/// Compute a fast approximation of `cos(PI * x)`.
+///
+/// The reduction of `x` modulo 2 is exact, so there's no error from
+/// multiplying by an approximation of &pi;: `cospi` is exactly
+/// &plusmn;1 at every integer, and exactly +0 at every
+/// half-integer. The maximum relative error across all f32s is less
+/// than 1.7e-6.
+///
+/// If `x` is NaN or infinite, `cospi` returns NaN.
+#[inline]
+pub fn cospi(x: f32) -> f32 {
+    let (r, n) = reduce(x);
+    let c = match n & 3 {
+        0 => cos_kernel(r),
+        1 => -sin_kernel(r),
+        2 => -cos_kernel(r),
+        _ => sin_kernel(r),
+    };
+    // turn -0 into +0, leaving everything else unchanged
+    c + 0.0
+}
+
+/// Compute a fast approximation of the sine of `t` turns, that is,
+/// `sin(2 * PI * t)`.
+///
+/// This has the same guarantees as `sinpi`, with zeros at every
+/// multiple of 1/2, and &plusmn;1 at every odd multiple of 1/4.
+#[inline]
+pub fn sin_turns(t: f32) -> f32 {
+    // doubling the fractional part is exact, and can't overflow
+    sinpi(2.0 * fract(t))
+}
+
+/// Compute a fast approximation of the cosine of `t` turns, that is,
+/// `cos(2 * PI * t)`.
+///
+/// This has the same guarantees as `cospi`, with zeros at every odd
+/// multiple of 1/4, and &plusmn;1 at every multiple of 1/2.
+#[inline]
+pub fn cos_turns(t: f32) -> f32 {
+    cospi(2.0 * fract(t))
+}
+
+/// (sin(x), cos(x)), sharing the range reduction of `tan`, with
+/// absolute error 2e-6 for `|x| <= 8192`, and in [-1, 1] beyond. Both
+/// are NaN if `x` is NaN or infinite.
+#[inline]
+pub(crate) fn sin_cos(x: f32) -> (f32, f32) {
+    if !x.is_finite() {
+        return (f32::NAN, f32::NAN)
+    }
+    let (r, j) = tan::reduce(x);
+    // the kernels work in half turns, and the remainder can stray
+    // from [-π/4, π/4] for `x` beyond the limit
+    let t = r * FRAC_1_PI;
+    let t = if x.abs() <= REDUCE_LIMIT { t } else { t.clamp(-0.25, 0.25) };
+    let (s, c) = (sin_kernel(t), cos_kernel(t));
+    match j & 3 {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+    use core::f64::consts::PI;
+
+    /// Maximal relative error of the kernels.
+    const TOL: f64 = 1.7e-6;
+
+    /// The relative error of `approx` against `exact`, which is
+    /// computed in half turns and so is exact at multiples of 1/2.
+    fn rel_err(approx: f32, exact: f64) -> f64 {
+        if exact == 0.0 {
+            approx.abs() as f64
+        } else {
+            (approx as f64 - exact).abs() / exact.abs()
+        }
+    }
+
+    /// `sin(PI * x)` and `cos(PI * x)` to f64 precision, reducing
+    /// exactly first.
+    fn exact(x: f32) -> (f64, f64) {
+        let x = x as f64 % 2.0;
+        let n = (2.0 * x).round();
+        let r = (x - n / 2.0) * PI;
+        let (s, c) = (r.sin(), r.cos());
+        match n as i32 & 3 {
+            0 => (s, c),
+            1 => (c, -s),
+            2 => (-s, -c),
+            _ => (-c, s),
+        }
+    }
+
+    #[test]
+    fn sinpi_cospi_rel_err_qc() {
+        fn prop(x: f32) -> qc::TestResult {
+            if !x.is_finite() { return qc::TestResult::discard() }
+            let (s, c) = exact(x);
+            qc::TestResult::from_bool(rel_err(sinpi(x), s) < TOL &&
+                                      rel_err(cospi(x), c) < TOL)
+        }
+        qc::quickcheck(prop as fn(f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn sinpi_cospi_rel_err_exhaustive() {
+        // every 13th float in [2^-24, 2]
+        let mut x = 1.0 / (1 << 24) as f32;
+        while x <= 2.0 {
+            for &x in &[x, -x] {
+                let (s, c) = exact(x);
+                let e = rel_err(sinpi(x), s);
+                assert!(e < TOL, "sinpi({:e}): {:e}", x, e);
+                let e = rel_err(cospi(x), c);
+                assert!(e < TOL, "cospi({:e}): {:e}", x, e);
+            }
+            x = f32::from_bits(x.to_bits() + 13);
+        }
+    }
+
+    #[test]
+    fn sinpi_cospi_large() {
+        let mut x = 2.0f32;
+        while x.is_finite() {
+            let (s, c) = exact(x);
+            assert!(rel_err(sinpi(x), s) < TOL, "{:e}", x);
+            assert!(rel_err(cospi(x), c) < TOL, "{:e}", x);
+            x = f32::from_bits(x.to_bits() + 1001);
+        }
+    }
+
+    #[test]
+    fn sin_cos_abs_err() {
+        let mut max = 0.0f64;
+        let mut x = 0.0f32;
+        while x <= REDUCE_LIMIT {
+            for &x in &[x, -x] {
+                let (s, c) = sin_cos(x);
+                let x64 = x as f64;
+                let err = (s as f64 - x64.sin()).abs().max((c as f64 - x64.cos()).abs());
+                if err > max { max = err }
+                assert!(err < 2e-6, "{:e}: {:e}", x, err);
+            }
+            x = f32::from_bits(x.to_bits() + 97);
+        }
+        println!("maximum {:e}", max);
+    }
+
+    #[test]
+    fn sin_cos_large() {
+        for &x in &[8193.0, 1e10, f32::MAX, -f32::MAX] {
+            let (s, c) = sin_cos(x);
+            assert!(s.abs() <= 1.0 && c.abs() <= 1.0, "{:e}", x);
+        }
+        for &x in &[f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let (s, c) = sin_cos(x);
+            assert!(s.is_nan() && c.is_nan(), "{:e}", x);
+        }
+    }
+
+    #[test]
+    fn sinpi_cospi_exact() {
+        for i in -1000..1000 {
+            let x = i as f32;
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            assert_eq!(sinpi(x), 0.0);
+            assert_eq!(cospi(x), sign);
+            assert_eq!(sinpi(x + 0.5), sign);
+            assert_eq!(cospi(x + 0.5).to_bits(), 0.0f32.to_bits(), "{}", x + 0.5);
+        }
+        for &x in &[INTEGRAL, 2.0 * INTEGRAL, 1e30, f32::MAX] {
+            assert_eq!(sinpi(x), 0.0);
+            assert_eq!(cospi(x), 1.0);
+        }
+        // the zeros have the sign of x, like IEEE 754 sinPi
+        for &x in &[0.0, 1.0, 2.0, 3.0, 1001.0, INTEGRAL - 1.0, INTEGRAL + 1.0,
+                    2.0 * INTEGRAL, 1e30, f32::MAX] {
+            assert!(sinpi(x).is_sign_positive(), "{}", x);
+            assert!(sinpi(-x).is_sign_negative(), "{}", -x);
+            assert!(sin_turns(x / 2.0).is_sign_positive(), "{}", x / 2.0);
+            assert!(sin_turns(-x / 2.0).is_sign_negative(), "{}", -x / 2.0);
+        }
+        assert_eq!(cospi(INTEGRAL + 1.0), -1.0);
+        assert_eq!(cospi(-INTEGRAL - 1.0), -1.0);
+    }
+
+    #[test]
+    fn sin_cos_turns_rel_err_qc() {
+        fn prop(t: f32) -> qc::TestResult {
+            if !t.is_finite() { return qc::TestResult::discard() }
+            let (s, c) = exact(2.0 * (t as f64 % 1.0) as f32);
+            qc::TestResult::from_bool(rel_err(sin_turns(t), s) < TOL &&
+                                      rel_err(cos_turns(t), c) < TOL)
+        }
+        qc::quickcheck(prop as fn(f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn sin_cos_turns_exact() {
+        for i in -1000..1000 {
+            let t = i as f32 / 4.0;
+            let (s, c) = match i & 3 {
+                0 => (0.0, 1.0),
+                1 => (1.0, 0.0),
+                2 => (0.0, -1.0),
+                _ => (-1.0, 0.0),
+            };
+            assert_eq!(sin_turns(t), s, "{}", t);
+            assert_eq!(cos_turns(t), c, "{}", t);
+        }
+        assert_eq!(sin_turns(f32::MAX), 0.0);
+        assert_eq!(cos_turns(f32::MAX), 1.0);
+    }
+
+    #[test]
+    fn edge_cases() {
+        for &x in &[f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert!(sinpi(x).is_nan());
+            assert!(cospi(x).is_nan());
+            assert!(sin_turns(x).is_nan());
+            assert!(cos_turns(x).is_nan());
+        }
+        assert_eq!(sinpi(-0.0).to_bits(), (-0.0f32).to_bits());
+        assert_eq!(sin_turns(-0.0).to_bits(), (-0.0f32).to_bits());
+        let tiny = f32::from_bits(1);
+        assert!(sinpi(tiny) > 0.0);
+        assert!(sin_turns(tiny) > 0.0);
+    }
+}
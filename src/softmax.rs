@@ -0,0 +1,256 @@
+use core::f32;
+use core::f32::consts::LN_2;
+use exp::exp_exact_anchors;
+use log::log2_accurate;
+
+/// The maximum of `xs`: NaN if any element is NaN, and -&infin; if
+/// `xs` is empty.
+#[inline]
+fn max(xs: &[f32]) -> f32 {
+    let mut m = f32::NEG_INFINITY;
+    for &x in xs {
+        if x > m {
+            m = x
+        } else if x.is_nan() {
+            return x
+        }
+    }
+    m
+}
+
+/// The sum of e<sup>x - m</sup> over `xs`, for finite `m = max(xs)`,
+/// so that every term is at most 1 and the sum is at least 1.
+#[inline]
+fn sum_exp(xs: &[f32], m: f32) -> f32 {
+    // `exp_exact_anchors(0.0)` is exactly 1, so the term for the
+    // maximum is exact
+    xs.iter().map(|&x| exp_exact_anchors(x - m)).sum()
+}
+
+/// Compute a fast approximation of ln(e<sup>x<sub>1</sub></sup> +
+/// e<sup>x<sub>2</sub></sup> + ...) for the elements of `xs`, without
+/// overflow.
+///
+/// The maximum is subtracted from every element before
+/// exponentiating, so large elements don't overflow to infinity and
+/// the result is finite whenever the elements are. The maximum
+/// absolute error is less than 0.003.
+///
+/// If `xs` is empty, or all its elements are -&infin;, `logsumexp`
+/// returns -&infin;. Otherwise, if any element is NaN, `logsumexp`
+/// returns NaN, and if any is &infin;, it returns &infin;.
+#[inline]
+pub fn logsumexp(xs: &[f32]) -> f32 {
+    let m = max(xs);
+    if m.is_finite() {
+        m + LN_2 * log2_accurate(sum_exp(xs, m))
+    } else {
+        // NaN, ∞, or -∞ for empty or all -∞
+        m
+    }
+}
+
+/// Compute a fast approximation of the softmax of `xs` into `out`,
+/// that is, e<sup>x<sub>i</sub></sup> / (e<sup>x<sub>1</sub></sup> +
+/// e<sup>x<sub>2</sub></sup> + ...) for each element x<sub>i</sub>.
+///
+/// The maximum is subtracted from every element before
+/// exponentiating, so large elements don't overflow to infinity. The
+/// maximum relative error of each output is less than 0.006, but
+/// they are all normalized by the same sum, so that the exact sum of
+/// the outputs is within `xs.len() * f32::EPSILON` of 1.
+///
+/// If any element of `xs` is NaN or &infin;, or all are -&infin;,
+/// every output is NaN.
+///
+/// # Panics
+///
+/// Panics if `xs` and `out` have different lengths.
+#[inline]
+pub fn softmax(xs: &[f32], out: &mut [f32]) {
+    assert_eq!(xs.len(), out.len(), "softmax: input and output lengths differ");
+    // for ∞ and all -∞, `x - m` is NaN for the maximum, and so the
+    // sum and every output are NaN
+    let m = max(xs);
+    let mut sum = 0.0;
+    for (o, &x) in out.iter_mut().zip(xs) {
+        *o = exp_exact_anchors(x - m);
+        sum += *o;
+    }
+    let scale = 1.0 / sum;
+    for o in out {
+        *o *= scale
+    }
+}
+
+/// Compute a fast approximation of the logarithm of the softmax of
+/// `xs` into `out`, that is, x<sub>i</sub> - `logsumexp(xs)` for each
+/// element x<sub>i</sub>.
+///
+/// This is more accurate than taking the logarithm of `softmax`, and
+/// doesn't underflow to -&infin; for very small probabilities. The
+/// maximum absolute error of each output is less than 0.003.
+///
+/// If any element of `xs` is NaN or &infin;, or all are -&infin;,
+/// every output is NaN.
+///
+/// # Panics
+///
+/// Panics if `xs` and `out` have different lengths.
+#[inline]
+pub fn log_softmax(xs: &[f32], out: &mut [f32]) {
+    assert_eq!(xs.len(), out.len(), "log_softmax: input and output lengths differ");
+    let m = max(xs);
+    let lse = if m.is_finite() { LN_2 * log2_accurate(sum_exp(xs, m)) } else { f32::NAN };
+    for (o, &x) in out.iter_mut().zip(xs) {
+        *o = (x - m) - lse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+    use std::{f32, f64};
+    use std::vec::Vec;
+
+    fn exact_logsumexp(xs: &[f32]) -> f64 {
+        let m = xs.iter().fold(f64::NEG_INFINITY, |m, &x| m.max(x as f64));
+        m + xs.iter().map(|&x| (x as f64 - m).exp()).sum::<f64>().ln()
+    }
+
+    /// Whether `xs` are finite and non-empty, so everything is
+    /// well-defined.
+    fn valid(xs: &[f32]) -> bool {
+        !xs.is_empty() && xs.iter().all(|x| x.is_finite())
+    }
+
+    #[test]
+    fn logsumexp_qc() {
+        fn prop(xs: Vec<f32>) -> qc::TestResult {
+            if !valid(&xs) { return qc::TestResult::discard() }
+            let e = logsumexp(&xs) as f64;
+            let t = exact_logsumexp(&xs);
+            // `m + ...` rounds to f32 too
+            let tol = 0.003 + t.abs() * f32::EPSILON as f64;
+            qc::TestResult::from_bool((e - t).abs() < tol)
+        }
+        qc::quickcheck(prop as fn(Vec<f32>) -> qc::TestResult)
+    }
+
+    #[test]
+    fn softmax_qc() {
+        fn prop(xs: Vec<f32>) -> qc::TestResult {
+            if !valid(&xs) { return qc::TestResult::discard() }
+            let mut out = vec![0.0; xs.len()];
+            softmax(&xs, &mut out);
+            let lse = exact_logsumexp(&xs);
+            let close = xs.iter().zip(&out).all(|(&x, &o)| {
+                let t = (x as f64 - lse).exp();
+                // outputs below the normal range lose relative accuracy
+                (o as f64 - t).abs() <= 0.006 * t + f32::MIN_POSITIVE as f64
+            });
+            let sum = out.iter().map(|&o| o as f64).sum::<f64>();
+            let sums_to_1 = (sum - 1.0).abs() <= xs.len() as f64 * f32::EPSILON as f64;
+            qc::TestResult::from_bool(close && sums_to_1)
+        }
+        qc::quickcheck(prop as fn(Vec<f32>) -> qc::TestResult)
+    }
+
+    #[test]
+    fn log_softmax_qc() {
+        fn prop(xs: Vec<f32>) -> qc::TestResult {
+            if !valid(&xs) { return qc::TestResult::discard() }
+            let mut out = vec![0.0; xs.len()];
+            log_softmax(&xs, &mut out);
+            let lse = exact_logsumexp(&xs);
+            qc::TestResult::from_bool(xs.iter().zip(&out).all(|(&x, &o)| {
+                let t = x as f64 - lse;
+                (o as f64 - t).abs() < 0.003 + t.abs() * f32::EPSILON as f64
+            }))
+        }
+        qc::quickcheck(prop as fn(Vec<f32>) -> qc::TestResult)
+    }
+
+    #[test]
+    fn pairs() {
+        // sweep the difference through the whole range where `exp`
+        // contributes error
+        let mut out = [0.0; 2];
+        let mut d = -100.0f32;
+        while d < 100.0 {
+            let xs = [0.0, d];
+            let lse = exact_logsumexp(&xs);
+            assert!((logsumexp(&xs) as f64 - lse).abs() < 0.003, "{}", d);
+
+            softmax(&xs, &mut out);
+            for (&x, &o) in xs.iter().zip(&out) {
+                let t = (x as f64 - lse).exp();
+                assert!((o as f64 - t).abs() <= 0.006 * t + f32::MIN_POSITIVE as f64,
+                        "{}: {:e} {:e}", d, o, t);
+            }
+            assert!(((out[0] as f64 + out[1] as f64) - 1.0).abs() <= 2.0 * f32::EPSILON as f64);
+
+            log_softmax(&xs, &mut out);
+            for (&x, &o) in xs.iter().zip(&out) {
+                let t = x as f64 - lse;
+                assert!((o as f64 - t).abs() < 0.003 + t.abs() * f32::EPSILON as f64,
+                        "{}: {:e} {:e}", d, o, t);
+            }
+            d += 0.001;
+        }
+    }
+
+    #[test]
+    fn no_overflow() {
+        let xs = [1000.0, 1000.0, f32::MAX, f32::MAX];
+        assert_eq!(logsumexp(&xs[..2]), 1000.0 + LN_2);
+        assert!(logsumexp(&xs[2..]).is_finite());
+
+        let mut out = [0.0; 4];
+        softmax(&xs, &mut out);
+        assert_eq!(out, [0.0, 0.0, 0.5, 0.5]);
+        log_softmax(&xs, &mut out);
+        assert_eq!(out[0], f32::MIN);
+        assert_eq!(out[2], out[3]);
+        assert!((out[2] + LN_2).abs() < 0.003);
+    }
+
+    #[test]
+    fn edge_cases() {
+        let inf = f32::INFINITY;
+        assert_eq!(logsumexp(&[]), -inf);
+        assert_eq!(logsumexp(&[-inf, -inf]), -inf);
+        assert_eq!(logsumexp(&[1.0, -inf]), logsumexp(&[1.0]));
+        assert_eq!(logsumexp(&[1.0, inf]), inf);
+        assert!(logsumexp(&[inf, f32::NAN]).is_nan());
+        assert!(logsumexp(&[f32::NAN, inf]).is_nan());
+
+        softmax(&[], &mut []);
+        log_softmax(&[], &mut []);
+        let mut out = [0.0; 3];
+        softmax(&[1.0, -inf, 1.0], &mut out);
+        assert_eq!(out, [0.5, 0.0, 0.5]);
+        log_softmax(&[1.0, -inf, 1.0], &mut out);
+        assert_eq!(out[1], -inf);
+
+        for xs in &[[1.0, f32::NAN, 0.0], [1.0, inf, 0.0], [-inf, -inf, -inf]] {
+            softmax(xs, &mut out);
+            assert!(out.iter().all(|o| o.is_nan()), "{:?}", xs);
+            log_softmax(xs, &mut out);
+            assert!(out.iter().all(|o| o.is_nan()), "{:?}", xs);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "softmax: input and output lengths differ")]
+    fn softmax_lengths() {
+        softmax(&[1.0, 2.0], &mut [0.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "log_softmax: input and output lengths differ")]
+    fn log_softmax_lengths() {
+        log_softmax(&[1.0], &mut [0.0, 0.0]);
+    }
+}
@@ -0,0 +1,52 @@
+use ieee754::Ieee754;
+
+/// Compute a fast approximation to the square root of **non-negative,
+/// finite, non-denormal** `x` (or zero).
+///
+/// This will return unspecified nonsense if `x` is doesn't not
+/// satisfy those constraints.
+///
+/// The maximum relative error across all valid input is less than
+/// 1.2e-7.
+#[inline]
+pub fn sqrt_raw(x: f32) -> f32 {
+    // approximate 1/sqrt(x) by halving the exponent in the bit
+    // representation, then refine with two Newton-Raphson steps...
+    const MAGIC: u32 = 0x5f37_5a86;
+    let r = f32::from_bits(MAGIC - (x.bits() >> 1));
+    let r = r * (1.5 - 0.5 * x * r * r);
+    let r = r * (1.5 - 0.5 * x * r * r);
+    // ...and one more on sqrt(x) = x / sqrt(x) itself.
+    let s = x * r;
+    s + 0.5 * r * (x - s * s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_raw_rel_err_exhaustive() {
+        let mut max = 0.0;
+        // every float in [1, 4) covers every combination of mantissa
+        // and exponent parity.
+        for bits in 1f32.bits()..4f32.bits() {
+            for &scale in &[1.0, 2f32.powi(-124), 2f32.powi(124)] {
+                let x = f32::from_bits(bits) * scale;
+                let e = sqrt_raw(x);
+                let t = x.sqrt();
+                let rel = e.rel_error(t).abs();
+                if rel > max { max = rel }
+                assert!(rel < 1.2e-7, "{:e}: {:e}, {:e}. {:e}", x, e, t, rel);
+            }
+        }
+        println!("maximum {}", max);
+    }
+
+    #[test]
+    fn sqrt_raw_edge_cases() {
+        assert_eq!(sqrt_raw(0.0), 0.0);
+        assert_eq!(sqrt_raw(1.0), 1.0);
+        assert_eq!(sqrt_raw(4.0), 2.0);
+    }
+}
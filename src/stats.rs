@@ -0,0 +1,220 @@
+//! The standard normal distribution, with mean 0 and variance 1.
+//!
+//! Other normal distributions can be handled by scaling: the density
+//! of N(&mu;, &sigma;<sup>2</sup>) at `x` is `normal_pdf((x - mu) /
+//! sigma) / sigma`, and its quantiles are `mu + sigma *
+//! normal_quantile(p)`.
+//!
+//! ```rust
+//! use fast_math::stats;
+//!
+//! assert!((stats::normal_cdf(1.96) - 0.975).abs() < 1e-4);
+//! assert!((stats::normal_quantile(0.975) - 1.96).abs() < 1e-3);
+//! ```
+
+use core::f32;
+use core::f32::consts::{SQRT_2, FRAC_1_SQRT_2};
+use erf::{erfc, erf_inv_ratio};
+use exp::exp;
+
+/// 1/&radic;(2&pi;).
+const FRAC_1_SQRT_2PI: f32 = 0.398942280401432677939946059934381868;
+/// ln &radic;(2&pi;).
+const LN_SQRT_2PI: f32 = 0.918938533204672741780329736405617640;
+
+/// Compute a fast approximation of the probability density of the
+/// standard normal distribution at `x`,
+/// e<sup>-x<sup>2</sup>/2</sup> / &radic;(2&pi;).
+///
+/// The maximum relative error for `|x| <= 13`, where the result is
+/// normal, is less than 0.011, as for `exp`. Beyond that, the result
+/// is subnormal or zero. Use `normal_logpdf` if the logarithm is
+/// needed, as that is more accurate, and doesn't underflow.
+///
+/// If `x` is NaN, `normal_pdf` returns NaN.
+#[inline]
+pub fn normal_pdf(x: f32) -> f32 {
+    FRAC_1_SQRT_2PI * exp(-0.5 * x * x)
+}
+
+/// Compute the logarithm of the probability density of the standard
+/// normal distribution at `x`, -x<sup>2</sup>/2 - ln &radic;(2&pi;).
+///
+/// This is computed directly, with only rounding error, so it is
+/// accurate to a few ulps for all `x`, even where `normal_pdf`
+/// underflows. `normal_logpdf(±∞)` is -&infin;.
+///
+/// If `x` is NaN, `normal_logpdf` returns NaN.
+#[inline]
+pub fn normal_logpdf(x: f32) -> f32 {
+    -0.5 * x * x - LN_SQRT_2PI
+}
+
+/// Compute a fast approximation of the cumulative distribution
+/// function of the standard normal distribution at `x`, that is, the
+/// probability that a sample is at most `x`.
+///
+/// The maximum absolute error across all f32s is less than 6e-6. The
+/// lower tail is computed via `erfc`, so that small probabilities keep
+/// their relative accuracy: for `x` &le; -2.9 the maximum relative
+/// error is less than 0.0022, while the result is normal (`x` &ge;
+/// -13). `normal_cdf(-∞)` is exactly 0 and `normal_cdf(∞)` is exactly
+/// 1.
+///
+/// If `x` is NaN, `normal_cdf` returns NaN.
+#[inline]
+pub fn normal_cdf(x: f32) -> f32 {
+    0.5 * erfc(-x * FRAC_1_SQRT_2)
+}
+
+/// Compute a fast approximation of the quantile function (probit) of
+/// the standard normal distribution, that is, the `x` with
+/// `normal_cdf(x) == p`.
+///
+/// The maximum relative error for 0 < `p` < 1 is less than 3.5e-5,
+/// including deep in both tails, since the distance of `p` from the
+/// nearer end is used directly. `normal_quantile(0)` is -&infin; and
+/// `normal_quantile(1)` is &infin;.
+///
+/// If `p` is NaN or outside [0, 1], `normal_quantile` returns NaN.
+#[inline]
+pub fn normal_quantile(p: f32) -> f32 {
+    // the quantile is -&radic;2 erf_inv(1 - q) in the lower tail, and
+    // &radic;2 erf_inv(1 - q) in the upper one, and 1 - (1 - q)^2 is
+    // computed from q itself (both 2p and 2 (1 - p) are exact for p on
+    // the relevant side of 0.5), so there is no cancellation
+    let lower = p < 0.5;
+    let q = 2.0 * if lower { p } else { 1.0 - p };
+    let x = if q > 0.0 {
+        SQRT_2 * (1.0 - q) * erf_inv_ratio(q * (2.0 - q))
+    } else if q == 0.0 {
+        f32::INFINITY
+    } else {
+        // out of range, or NaN
+        return f32::NAN
+    };
+    if lower { -x } else { x }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reference::exact_erfc;
+    use quickcheck as qc;
+    use std::f64::consts::{PI, SQRT_2};
+    use std::f32;
+
+    fn exact_cdf(x: f64) -> f64 {
+        0.5 * exact_erfc(-x / SQRT_2)
+    }
+
+    fn exact_pdf(x: f64) -> f64 {
+        (-0.5 * x * x).exp() / (2.0 * PI).sqrt()
+    }
+
+    /// The quantile at `p`, by Newton's method starting at `guess`, in
+    /// the lower tail to avoid cancellation.
+    fn exact_quantile(p: f32, guess: f32) -> f64 {
+        if p > 0.5 {
+            return -exact_quantile(1.0 - p, -guess)
+        }
+        let p = p as f64;
+        let mut x = guess as f64;
+        for _ in 0..50 {
+            let step = (exact_cdf(x) - p) / exact_pdf(x);
+            x -= step;
+            if step.abs() <= 1e-15 * x.abs() { break }
+        }
+        x
+    }
+
+    /// Every 61st f32 in [-20, 20], of both signs.
+    fn inputs() -> impl Iterator<Item = f32> {
+        (0..20.0f32.to_bits()).step_by(61)
+            .map(f32::from_bits)
+            .flat_map(|x| vec![x, -x])
+    }
+
+    #[test]
+    fn pdf_rel_err() {
+        for x in inputs().filter(|x| x.abs() <= 13.0) {
+            let t = exact_pdf(x as f64);
+            let e = normal_pdf(x) as f64;
+            assert!((e - t).abs() < 0.011 * t, "{:e}: {:e} {:e}", x, e, t);
+        }
+    }
+
+    #[test]
+    fn logpdf_err() {
+        for x in inputs() {
+            let x64 = x as f64;
+            let t = -0.5 * x64 * x64 - (2.0 * PI).sqrt().ln();
+            let e = normal_logpdf(x) as f64;
+            assert!((e - t).abs() <= 3.0 * f32::EPSILON as f64 * t.abs(),
+                    "{:e}: {:e} {:e}", x, e, t);
+        }
+    }
+
+    #[test]
+    fn cdf_err() {
+        for x in inputs() {
+            let t = exact_cdf(x as f64);
+            let e = normal_cdf(x) as f64;
+            assert!((e - t).abs() < 6e-6, "{:e}: {:e} {:e}", x, e, t);
+            if (-13.0..=-2.9).contains(&x) {
+                assert!((e - t).abs() < 0.0022 * t, "{:e}: {:e} {:e}", x, e, t);
+            }
+        }
+    }
+
+    #[test]
+    fn quantile_rel_err_exhaustive() {
+        // every 4099th f32 in (0, 1)
+        let mut p = f32::from_bits(1);
+        while p < 1.0 {
+            let e = normal_quantile(p);
+            let t = exact_quantile(p, e);
+            let rel = (e as f64 - t).abs() / t.abs();
+            assert!(rel < 3.5e-5, "{:e}: {:e} {:e} {:e}", p, e, t, rel);
+            p = f32::from_bits(p.to_bits() + 4099);
+        }
+    }
+
+    #[test]
+    fn quantile_round_trip_qc() {
+        fn prop(x: f32) -> qc::TestResult {
+            let p = normal_cdf(x);
+            if !(p > 0.0 && p < 1.0) {
+                return qc::TestResult::discard()
+            }
+            // cdf(quantile(p)) is close to p, as cdf's error dominates
+            let e = normal_cdf(normal_quantile(p));
+            qc::TestResult::from_bool((e - p).abs() < 1.5e-5)
+        }
+        qc::quickcheck(prop as fn(f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert!(normal_pdf(f32::NAN).is_nan());
+        assert_eq!(normal_pdf(f32::INFINITY), 0.0);
+        assert_eq!(normal_pdf(f32::NEG_INFINITY), 0.0);
+
+        assert!(normal_logpdf(f32::NAN).is_nan());
+        assert_eq!(normal_logpdf(f32::INFINITY), f32::NEG_INFINITY);
+        assert_eq!(normal_logpdf(f32::MAX), f32::NEG_INFINITY);
+
+        assert!(normal_cdf(f32::NAN).is_nan());
+        assert_eq!(normal_cdf(f32::INFINITY), 1.0);
+        assert_eq!(normal_cdf(f32::NEG_INFINITY), 0.0);
+        assert_eq!(normal_cdf(0.0), 0.5);
+
+        assert_eq!(normal_quantile(0.5), 0.0);
+        assert_eq!(normal_quantile(0.0), f32::NEG_INFINITY);
+        assert_eq!(normal_quantile(-0.0), f32::NEG_INFINITY);
+        assert_eq!(normal_quantile(1.0), f32::INFINITY);
+        for &p in &[f32::NAN, -1e-30, 1.0 + f32::EPSILON, f32::INFINITY, f32::NEG_INFINITY] {
+            assert!(normal_quantile(p).is_nan(), "{}", p);
+        }
+    }
+}
@@ -0,0 +1,551 @@
+//! Table-driven approximations, trading memory for accuracy.
+//!
+//! Each function here takes the number of table entries `N` as a
+//! const generic parameter, which must be a power of two, at least
+//! 2. The tables are computed at compile time, and only the sizes
+//! that are actually used end up in the final binary: `exp2` and
+//! `atan` use `4 N` bytes, and `log2` uses `12 N` bytes.
+//!
+//! ```rust
+//! use fast_math::tables;
+//!
+//! let x = 10.4781;
+//! let approx = tables::log2::<64>(x);
+//! let real = x.log2();
+//! assert!((approx - real).abs() < 1e-6);
+//! ```
+//!
+//! This module requires the `tables` feature.
+
+use core::f32::consts::FRAC_PI_2;
+use core::f64::consts::LN_2;
+use float::{self, flip_sign_nonnan};
+use ieee754::Ieee754;
+
+// Everything in this section is only evaluated at compile time, and
+// so can afford to be slow and (relatively) precise.
+
+const fn check_size(n: usize) {
+    assert!(n >= 2 && n.is_power_of_two(),
+            "table size must be a power of two, at least 2");
+}
+
+/// *e*<sup>`x`</sup> for |`x`| &le; 1.
+const fn exp_f64(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut n = 1;
+    while n < 30 {
+        term *= x / n as f64;
+        sum += term;
+        n += 1;
+    }
+    sum
+}
+
+/// ln(`x`) for 0.5 &le; `x` &le; 2.
+const fn ln_f64(x: f64) -> f64 {
+    // ln(x) = 2 atanh(s)
+    let s = (x - 1.0) / (x + 1.0);
+    let mut sum = 0.0;
+    let mut power = s;
+    let mut n = 0;
+    while n < 40 {
+        sum += power / (2 * n + 1) as f64;
+        power *= s * s;
+        n += 1;
+    }
+    2.0 * sum
+}
+
+/// atan(`x`) for 0 &le; `x` &le; 1.
+const fn atan_f64(x: f64) -> f64 {
+    // Euler's series, which converges at least as fast as 2^-n on
+    // this interval.
+    let y = x * x / (1.0 + x * x);
+    let mut term = x / (1.0 + x * x);
+    let mut sum = 0.0;
+    let mut n = 0;
+    while n < 80 {
+        sum += term;
+        term *= y * (2 * n + 2) as f64 / (2 * n + 3) as f64;
+        n += 1;
+    }
+    sum
+}
+
+struct Exp2Table<const N: usize>;
+impl<const N: usize> Exp2Table<N> {
+    /// 2<sup>j/N</sup>
+    const TABLE: [f32; N] = {
+        check_size(N);
+        let mut table = [0.0; N];
+        let mut j = 0;
+        while j < N {
+            table[j] = exp_f64(j as f64 / N as f64 * LN_2) as f32;
+            j += 1;
+        }
+        table
+    };
+}
+
+/// The reduced argument of `log2` lies in [`LOG2_OFF`, 2 `LOG2_OFF`),
+/// chosen so that 1 is never at the edge of a subinterval.
+const LOG2_OFF: u32 = 0x3f80_0000 - 0x004a_aaab;
+
+struct Log2Table<const N: usize>;
+impl<const N: usize> Log2Table<N> {
+    /// (c, 1/c, log<sub>2</sub>(c)) for some c in each of the `N`
+    /// subintervals of [`LOG2_OFF`, 2 `LOG2_OFF`).
+    const TABLE: [(f32, f32, f32); N] = {
+        check_size(N);
+        let step = 1 << (float::SIGNIF as u32 - N.trailing_zeros());
+        let mut table = [(0.0, 0.0, 0.0); N];
+        let mut j = 0;
+        while j < N {
+            let lo = LOG2_OFF + j as u32 * step;
+            let c = if lo <= 0x3f80_0000 && 0x3f80_0000 < lo + step {
+                // use exactly 1 for the subinterval containing 1, so
+                // that log2(x) has small relative error near x = 1.
+                1.0
+            } else {
+                f32::from_bits(lo + step / 2)
+            };
+            table[j] = (c, (1.0 / c as f64) as f32, (ln_f64(c as f64) / LN_2) as f32);
+            j += 1;
+        }
+        table
+    };
+}
+
+struct AtanTable<const N: usize>;
+impl<const N: usize> AtanTable<N> {
+    /// atan(j/N)
+    const TABLE: [f32; N] = {
+        check_size(N);
+        let mut table = [0.0; N];
+        let mut j = 0;
+        while j < N {
+            table[j] = atan_f64(j as f64 / N as f64) as f32;
+            j += 1;
+        }
+        table
+    };
+}
+
+/// Compute a table-driven approximation to 2<sup><code>x</code></sup>
+/// for -126 &le; `x` < 128, using a table of `N` entries.
+///
+/// This will return unspecified nonsense if `x` does not satisfy
+/// those requirements. Use `exp2` if correct handling is required (at
+/// the expense of some speed).
+///
+/// The maximum relative error for each table size is:
+///
+/// | `N`  | Relative error |
+/// |-----:|---------------:|
+/// | 4    | 3.3e-5         |
+/// | 16   | 3.0e-7         |
+/// | 64   | 1.8e-7         |
+/// | 256  | 1.8e-7         |
+///
+/// Tables larger than 64 entries give no further benefit, since the
+/// error is then dominated by the rounding of the `f32` result.
+#[inline]
+pub fn exp2_raw<const N: usize>(x: f32) -> f32 {
+    check_raw!((-126.0..128.0).contains(&x), "tables::exp2_raw", x, "[-126, 128)");
+    const C1: f32 = LN_2 as f32;
+    const C2: f32 = (LN_2 * LN_2 / 2.0) as f32;
+    const C3: f32 = (LN_2 * LN_2 * LN_2 / 6.0) as f32;
+    let table = &Exp2Table::<N>::TABLE;
+
+    // x = i / N + r, with both scalings exact.
+    let scaled = x * N as f32;
+    let mut i = scaled as i32;
+    if i as f32 > scaled {
+        i -= 1;
+    }
+    let r = (scaled - i as f32) * (1.0 / N as f32);
+
+    let j = i as usize & (N - 1);
+    let k = i >> N.trailing_zeros();
+    let approx = table[j] * (1.0 + r * (C1 + r * (C2 + r * C3)));
+    f32::from_bits(approx.bits().wrapping_add((k as u32) << float::SIGNIF))
+}
+
+/// Compute a table-driven approximation to 2<sup><code>x</code></sup>,
+/// using a table of `N` entries.
+///
+/// The maximum relative error for inputs for which the result is
+/// normal (`x` &ge; -126) is the same as `exp2_raw`. Subnormal
+/// results are correctly scaled, and so only suffer an additional
+/// error of half the smallest subnormal from the final rounding.
+///
+/// If `x` is NaN, `exp2` returns NaN.
+#[inline]
+pub fn exp2<const N: usize>(x: f32) -> f32 {
+    if x < -126.0 {
+        if x < -150.0 {
+            0.0
+        } else {
+            const EXP2_M64: f32 = 5.421011e-20;
+            exp2_raw::<N>(x + 64.0) * EXP2_M64
+        }
+    } else if x < 128.0 {
+        exp2_raw::<N>(x)
+    } else {
+        // too big, or NaN, so lets overflow to infinity with some
+        // arithmetic to propagate the NaN.
+        x + f32::INFINITY
+    }
+}
+
+/// Compute a table-driven approximation of the base-2 logarithm of
+/// **positive, finite, non-denormal** `x`, using a table of `N`
+/// entries.
+///
+/// This will return unspecified nonsense if `x` is doesn't not
+/// satisfy those constraints. Use `log2` if correct handling is
+/// required (at the expense of some speed).
+///
+/// The maximum absolute and relative errors across all valid input
+/// for each table size are:
+///
+/// | `N`  | Absolute error | Relative error |
+/// |-----:|---------------:|---------------:|
+/// | 4    | 3.7e-5         | 1.5e-4         |
+/// | 16   | 7.7e-6         | 6.8e-7         |
+/// | 64   | 7.7e-6         | 3.1e-7         |
+/// | 256  | 7.7e-6         | 2.0e-7         |
+///
+/// For larger tables, the absolute error is dominated by the
+/// rounding of results near &plusmn;128 to `f32`, and tables larger
+/// than 256 entries give no further benefit.
+#[inline]
+pub fn log2_raw<const N: usize>(x: f32) -> f32 {
+    check_raw!((f32::MIN_POSITIVE..=f32::MAX).contains(&x), "tables::log2_raw", x,
+               "[1.1754944e-38, 3.4028235e38]");
+    const C1: f32 = (1.0 / LN_2) as f32;
+    const C2: f32 = (-1.0 / (2.0 * LN_2)) as f32;
+    const C3: f32 = (1.0 / (3.0 * LN_2)) as f32;
+    const C4: f32 = (-1.0 / (4.0 * LN_2)) as f32;
+    let table = &Log2Table::<N>::TABLE;
+
+    // x = 2^k z, with LOG2_OFF <= z < 2 LOG2_OFF
+    let bits = x.bits();
+    let tmp = bits.wrapping_sub(LOG2_OFF);
+    let j = (tmp >> (float::SIGNIF as u32 - N.trailing_zeros())) as usize & (N - 1);
+    let k = (tmp as i32) >> float::SIGNIF;
+    let z = f32::from_bits(bits.wrapping_sub(tmp & 0xFF80_0000));
+
+    // z - c is exact, so r has small relative error even when it is
+    // tiny.
+    let (c, invc, log2c) = table[j];
+    let r = (z - c) * invc;
+    (k as f32 + log2c) + r * (C1 + r * (C2 + r * (C3 + r * C4)))
+}
+
+/// Compute a table-driven approximation of the base-2 logarithm of
+/// `x`, using a table of `N` entries.
+///
+/// The maximum errors across all positive f32s (including denormals)
+/// are the same as `log2_raw`.
+///
+/// If `x` is negative, or NaN, `log2` returns `NaN`.
+#[inline]
+pub fn log2<const N: usize>(x: f32) -> f32 {
+    let (sign, exp, signif) = x.decompose_raw();
+    if sign {
+        f32::NAN
+    } else if exp == 0 {
+        if signif == 0 {
+            f32::NEG_INFINITY
+        } else {
+            // denormal
+            const EXP2_23: f32 = 8388608.0;
+            log2_raw::<N>(x * EXP2_23) - 23.0
+        }
+    } else if exp == 0xFF {
+        if signif == 0 {
+            f32::INFINITY
+        } else {
+            f32::NAN
+        }
+    } else {
+        log2_raw::<N>(x)
+    }
+}
+
+/// Compute a table-driven approximation of the inverse tangent for
+/// `|x| <= 1`, using a table of `N` entries.
+///
+/// This will return unspecified nonsense if `x` is doesn't not
+/// satisfy those constraints. Use `atan` if correct handling is
+/// required (at the expense of some speed).
+///
+/// The maximum absolute error for each table size is:
+///
+/// | `N`  | Absolute error |
+/// |-----:|---------------:|
+/// | 4    | 8.4e-6         |
+/// | 16   | 6.1e-8         |
+/// | 64   | 6.1e-8         |
+/// | 256  | 6.1e-8         |
+///
+/// Tables larger than 16 entries give no further benefit, since the
+/// error is then dominated by the rounding of the `f32` result.
+#[inline]
+pub fn atan_raw<const N: usize>(x: f32) -> f32 {
+    check_raw!(x.abs() <= 1.0, "tables::atan_raw", x, "[-1, 1]");
+    atan_kernel::<N>(x)
+}
+
+/// `atan_raw`, without the `strict-raw` check.
+#[inline(always)]
+fn atan_kernel<const N: usize>(x: f32) -> f32 {
+    let table = &AtanTable::<N>::TABLE;
+
+    // atan(a) = atan(c) + atan((a - c) / (1 + a c)), for c = j / N
+    // just below a.
+    let a = x.abs();
+    let j = ((a * N as f32) as usize).min(N - 1);
+    let c = j as f32 * (1.0 / N as f32);
+    let t = (a - c) / (1.0 + a * c);
+    let t2 = t * t;
+    let approx = table[j] + t * (1.0 + t2 * (-1.0 / 3.0 + t2 * (1.0 / 5.0)));
+    approx.copy_sign(x)
+}
+
+/// Compute a table-driven approximation of the arctangent of `x`,
+/// using a table of `N` entries.
+///
+/// The maximum absolute error across all f32s is less than 8.4e-6
+/// for `N = 4`, and less than 1.9e-7 for larger tables.
+#[inline]
+pub fn atan<const N: usize>(x: f32) -> f32 {
+    if x.abs() > 1.0 {
+        // if x is NaN, abs(x) is NaN, so the comparison can't succeed
+        debug_assert!(!x.is_nan());
+        flip_sign_nonnan(x, FRAC_PI_2) - atan_raw::<N>(1. / x)
+    } else {
+        // NaN ends up here, and propagates
+        atan_kernel::<N>(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num;
+
+    const PREC: u32 = 1 << 16;
+
+    /// Run `$test::<N>(tolerance)` for several table sizes `N`.
+    macro_rules! for_each_size {
+        ($test: ident, $tols: expr) => {{
+            let tols: [f64; 4] = $tols;
+            $test::<4>(tols[0]);
+            $test::<16>(tols[1]);
+            $test::<64>(tols[2]);
+            $test::<256>(tols[3]);
+        }}
+    }
+
+    fn rel_error(e: f32, t: f64) -> f64 {
+        ((e as f64 - t) / t).abs()
+    }
+
+    #[test]
+    fn exp2_rel_err_exhaustive() {
+        fn test<const N: usize>(tol: f64) {
+            let mut max = 0.0;
+            for i in 0..PREC + 1 {
+                for j in -4..4 {
+                    for &sign in &[-1.0, 1.0] {
+                        let x = sign * (1.0 + i as f32 / PREC as f32) * 2f32.powi(j * 2);
+                        let e = exp2::<N>(x);
+                        let t = (x as f64).exp2();
+                        let rel = rel_error(e, t);
+                        if (t as f32).is_infinite() {
+                            assert_eq!(e, f32::INFINITY);
+                        } else if (t as f32).classify() == num::FpCategory::Subnormal {
+                            assert!(rel <= 1.0,
+                                    "{}, {:.8}: e = {:.8e}, t = {:.8e}. {:.4}", N, x, e, t, rel);
+                        } else {
+                            if rel > max { max = rel }
+                            assert!(rel <= tol,
+                                    "{}, {:.8}: e = {:.8e}, t = {:.8e}. {:.4e}", N, x, e, t, rel);
+                        }
+                    }
+                }
+            }
+            println!("{}: maximum {:e}", N, max);
+        }
+        for_each_size!(test, [3.3e-5, 3.0e-7, 1.8e-7, 1.8e-7]);
+    }
+
+    #[test]
+    fn exp2_subnormal() {
+        fn test<const N: usize>(tol: f64) {
+            for i in 0..PREC + 1 {
+                let x = -126.0 - 25.0 * i as f32 / PREC as f32;
+                let e = exp2::<N>(x) as f64;
+                let t = (x as f64).exp2();
+                // the usual error, plus rounding to the nearest subnormal
+                assert!((e - t).abs() <= tol * t + 2f64.powi(-150),
+                        "{}, {:.8}: e = {:.8e}, t = {:.8e}", N, x, e, t);
+            }
+        }
+        for_each_size!(test, [3.3e-5, 3.0e-7, 1.8e-7, 1.8e-7]);
+    }
+
+    #[test]
+    fn exp2_edge_cases() {
+        fn test<const N: usize>(_tol: f64) {
+            assert!(exp2::<N>(f32::NAN).is_nan());
+            assert_eq!(exp2::<N>(f32::NEG_INFINITY), 0.0);
+            assert_eq!(exp2::<N>(-151.0), 0.0);
+            assert_eq!(exp2::<N>(0.0), 1.0);
+            assert_eq!(exp2::<N>(128.0), f32::INFINITY);
+            assert_eq!(exp2::<N>(f32::INFINITY), f32::INFINITY);
+        }
+        for_each_size!(test, [0.0; 4]);
+    }
+
+    #[test]
+    fn log2_rel_err_exhaustive() {
+        fn test<const N: usize>(tol: f64) {
+            let mut max = 0.0;
+            for i in 0..PREC + 1 {
+                for j in -7..7 {
+                    let x = (1.0 + i as f32 / PREC as f32) * 2f32.powi(j * 20);
+                    let e = log2::<N>(x);
+                    let t = (x as f64).log2();
+                    if t == 0.0 || t.is_infinite() {
+                        assert_eq!(e as f64, t);
+                        continue
+                    }
+                    let rel = rel_error(e, t);
+                    if rel > max { max = rel }
+                    assert!(rel <= tol, "{}, {:.8}: {:.8}, {:.8}. {:.4e}", N, x, e, t, rel);
+                }
+            }
+            println!("{}: maximum {:e}", N, max);
+        }
+        for_each_size!(test, [1.5e-4, 6.8e-7, 3.1e-7, 2.0e-7]);
+    }
+
+    #[test]
+    fn log2_abs_err_exhaustive() {
+        fn test<const N: usize>(tol: f64) {
+            for i in 0..PREC + 1 {
+                for j in -5..6 {
+                    let x = (1.0 + i as f32 / PREC as f32) * 2f32.powi(j * 20);
+                    let e = log2::<N>(x);
+                    let t = (x as f64).log2();
+                    if t.is_infinite() {
+                        assert_eq!(e as f64, t);
+                        continue
+                    }
+                    let abs = (e as f64 - t).abs();
+                    assert!(abs <= tol, "{}, {:.8}: {:.8}, {:.8}. {:.4e}", N, x, e, t, abs);
+                }
+            }
+        }
+        for_each_size!(test, [3.7e-5, 7.7e-6, 7.7e-6, 7.7e-6]);
+    }
+
+    #[test]
+    fn log2_near_one() {
+        fn test<const N: usize>(tol: f64) {
+            let mut x = 1.0f32;
+            let mut y = 1.0f32;
+            for _ in 0..PREC {
+                x = x.next();
+                y = y.prev();
+                for &z in &[x, y] {
+                    let e = log2::<N>(z);
+                    let t = (z as f64).log2();
+                    assert!(rel_error(e, t) <= tol, "{}, {:.8}: {:.8e}, {:.8e}", N, z, e, t);
+                }
+            }
+        }
+        for_each_size!(test, [1.5e-4, 6.8e-7, 3.1e-7, 2.0e-7]);
+    }
+
+    #[test]
+    fn log2_denormals() {
+        fn test<const N: usize>(tol: f64) {
+            for signif in 1..PREC {
+                let x = f32::recompose_raw(false, 0, signif * 97);
+                let e = log2::<N>(x);
+                let t = (x as f64).log2();
+                assert!(rel_error(e, t) <= tol, "{}, {:e}: {:.8e}, {:.8e}", N, x, e, t);
+            }
+        }
+        for_each_size!(test, [1.5e-4, 6.8e-7, 3.1e-7, 2.0e-7]);
+    }
+
+    #[test]
+    fn log2_edge_cases() {
+        fn test<const N: usize>(_tol: f64) {
+            assert!(log2::<N>(f32::NAN).is_nan());
+            assert!(log2::<N>(-1.0).is_nan());
+            assert!(log2::<N>(f32::NEG_INFINITY).is_nan());
+            assert_eq!(log2::<N>(f32::INFINITY), f32::INFINITY);
+            assert_eq!(log2::<N>(0.0), f32::NEG_INFINITY);
+            assert_eq!(log2::<N>(1.0), 0.0);
+            assert_eq!(log2::<N>(f32::recompose_raw(false, 0, 1)), -149.0);
+        }
+        for_each_size!(test, [0.0; 4]);
+    }
+
+    #[test]
+    fn atan_abs_err_exhaustive() {
+        fn test<const N: usize>(tol: f64) {
+            let mut max = 0.0;
+            for i in 0..PREC + 1 {
+                for j in -5..6 {
+                    for &sign in &[-1.0, 1.0] {
+                        let x = sign * (1.0 + i as f32 / PREC as f32) * 2f32.powi(j * 20);
+                        let e = atan::<N>(x);
+                        let t = (x as f64).atan();
+                        let abs = (e as f64 - t).abs();
+                        if abs > max { max = abs }
+                        assert!(abs <= tol, "{}, {:.8}: {:.8}, {:.8}. {:.4e}", N, x, e, t, abs);
+                    }
+                }
+            }
+            println!("{}: maximum {:e}", N, max);
+        }
+        for_each_size!(test, [8.4e-6, 1.9e-7, 1.9e-7, 1.9e-7]);
+    }
+
+    #[test]
+    fn atan_edge_cases() {
+        use core::f32::consts::PI;
+        fn test<const N: usize>(_tol: f64) {
+            assert!(atan::<N>(f32::NAN).is_nan());
+            assert_eq!(atan::<N>(f32::NEG_INFINITY), -PI / 2.);
+            assert_eq!(atan::<N>(0.), 0.);
+            assert_eq!(atan::<N>(-0.).to_bits(), (-0.0f32).to_bits());
+            assert_eq!(atan::<N>(f32::INFINITY), PI / 2.);
+        }
+        for_each_size!(test, [0.0; 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "strict-raw")]
+    fn raw_strict_domain() {
+        ::float::assert_raw_domains(&[
+            (exp2_raw::<16>, &[-126.0, 128.0f32.prev()], &[-127.0, 128.0, f32::NAN]),
+            (log2_raw::<16>, &[f32::MIN_POSITIVE, f32::MAX], &[1e-40, 0.0, -2.0, f32::INFINITY, f32::NAN]),
+            (atan_raw::<16>, &[-1.0, 1.0], &[-1.5, 1.5, f32::NAN]),
+        ]);
+        // the full functions never trip the checks
+        assert!(exp2::<16>(f32::NAN).is_nan());
+        assert!(exp2::<16>(-140.0) > 0.0);
+        assert!(log2::<16>(1e-40) < 0.0);
+        assert!(atan::<16>(f32::NAN).is_nan());
+    }
+}
@@ -0,0 +1,194 @@
+use core::f32::consts::{FRAC_2_PI, FRAC_PI_4};
+use ieee754::Ieee754;
+
+/// π/2 split into three parts for Cody-Waite range reduction: the
+/// first two have few enough significant bits that multiplying by
+/// the quadrant number is exact.
+const PIO2_1: f32 = 1.5703125;
+#[allow(clippy::excessive_precision)]
+const PIO2_2: f32 = 4.837512969970703125e-4;
+#[allow(clippy::excessive_precision)]
+const PIO2_3: f32 = 7.54978995489188216e-8;
+
+/// The largest magnitude for which the range reduction is accurate.
+pub(crate) const REDUCE_LIMIT: f32 = 8192.0;
+
+/// Minimax approximation of tan on [-π/4, π/4], with relative error
+/// 3.6e-6.
+#[inline]
+#[allow(clippy::excessive_precision)]
+fn tan_kernel(r: f32) -> f32 {
+    const C0: f32 = 0.333154333;
+    const C1: f32 = 0.1360650621;
+    const C2: f32 = 0.04139854569;
+    const C3: f32 = 0.04308880699;
+    let r2 = r * r;
+    r + r * r2 * (C0 + r2 * (C1 + r2 * (C2 + r2 * C3)))
+}
+
+/// Reduce `x` modulo π/2, returning the remainder in [-π/4, π/4]
+/// (approximately), and the quadrant.
+#[inline]
+pub(crate) fn reduce(x: f32) -> (f32, i32) {
+    let j = (x * FRAC_2_PI + 0.5f32.copy_sign(x)) as i32;
+    let jf = j as f32;
+    let r = ((x - jf * PIO2_1) - jf * PIO2_2) - jf * PIO2_3;
+    (r, j)
+}
+
+/// Compute a fast approximation of the tangent for `|x| <= 8192`.
+///
+/// This will return unspecified nonsense if `x` is doesn't not
+/// satisfy those constraints. Use `tan` if correct handling is
+/// required (at the expense of some speed).
+///
+/// The maximum relative error for `|x| < 128` is less than 4e-6. For
+/// larger `x`, the range reduction loses a few bits close to the
+/// poles, and the maximum relative error is less than 1e-4.
+///
+/// The result is finite for all valid input: at the floats closest to
+/// the poles, it is a large finite value with the correct sign.
+#[inline]
+pub fn tan_raw(x: f32) -> f32 {
+    check_raw!(x.abs() <= REDUCE_LIMIT, "tan_raw", x, "[-8192, 8192]");
+    let (r, j) = reduce(x);
+    let t = tan_kernel(r);
+    if j & 1 != 0 { -1.0 / t } else { t }
+}
+
+/// Compute a fast approximation of the tangent of `x`.
+///
+/// For `|x| <= 8192`, the error is as described in `tan_raw`. For
+/// larger `x`, the spacing between floats is too large relative to π
+/// for the reduction to be meaningful, and the result is finite but
+/// of unspecified accuracy.
+///
+/// If `x` is NaN or infinite, `tan` returns NaN. It never returns NaN
+/// for a finite `x`.
+///
+/// See also `tan_raw` which only works on `|x| <= 8192`, but is
+/// faster.
+#[inline]
+pub fn tan(x: f32) -> f32 {
+    let abs = x.abs();
+    if abs <= REDUCE_LIMIT {
+        tan_raw(x)
+    } else if abs < f32::INFINITY {
+        // keep the remainder in the range of the kernel, and away
+        // from zero so that the reciprocal is finite
+        let (r, j) = reduce(x);
+        let r = r.abs().clamp(f32::MIN_POSITIVE, FRAC_PI_4).copy_sign(r);
+        let t = tan_kernel(r);
+        if j & 1 != 0 { -1.0 / t } else { t }
+    } else {
+        // infinite, or NaN
+        f32::NAN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck as qc;
+    use core::f32::consts::{PI, FRAC_PI_2};
+
+    /// Maximal relative error of the kernel.
+    const TOL: f64 = 4e-6;
+
+    fn rel_err(x: f32) -> f64 {
+        let t = (x as f64).tan();
+        (tan(x) as f64 - t).abs() / t.abs()
+    }
+
+    /// The bound on relative error of `tan_raw`.
+    fn bound(x: f32) -> f64 {
+        if x.abs() < 128.0 { TOL } else { 1e-4 }
+    }
+
+    #[test]
+    fn tan_rel_err_qc() {
+        fn prop(x: f32) -> qc::TestResult {
+            if x.is_nan() || x.abs() > REDUCE_LIMIT || x == 0.0 { return qc::TestResult::discard() }
+            qc::TestResult::from_bool(rel_err(x) < bound(x))
+        }
+        qc::quickcheck(prop as fn(f32) -> qc::TestResult)
+    }
+
+    #[test]
+    fn tan_rel_err_principal() {
+        // every 11th float in [2^-20, π/2]
+        let mut x = 1.0 / (1 << 20) as f32;
+        while x <= FRAC_PI_2 {
+            for &x in &[x, -x] {
+                let e = rel_err(x);
+                assert!(e < TOL, "{:e}: {:e}", x, e);
+            }
+            x = f32::from_bits(x.to_bits() + 11);
+        }
+    }
+
+    #[test]
+    fn tan_rel_err_sampled() {
+        let mut x = 1.0 / (1 << 20) as f32;
+        while x <= REDUCE_LIMIT {
+            for &x in &[x, -x] {
+                let e = rel_err(x);
+                assert!(e < bound(x), "{:e}: {:e}", x, e);
+            }
+            x = f32::from_bits(x.to_bits() + 97);
+        }
+    }
+
+    #[test]
+    fn tan_near_poles() {
+        for k in -2000..2000 {
+            let pole = (k as f64 + 0.5) * core::f64::consts::PI;
+            let mut x = pole as f32;
+            let mut prev = x.prev();
+            for _ in 0..50 {
+                for &x in &[x, prev] {
+                    let e = tan(x);
+                    assert!(e.is_finite(), "{:e}", x);
+                    assert!(rel_err(x) < bound(x), "{:e}: {:e}", x, rel_err(x));
+                }
+                x = x.next();
+                prev = prev.prev();
+            }
+        }
+    }
+
+    #[test]
+    fn tan_large_is_finite() {
+        let mut x = REDUCE_LIMIT;
+        while x < f32::INFINITY {
+            assert!(tan(x).is_finite(), "{:e}", x);
+            assert!(tan(-x).is_finite(), "{:e}", x);
+            x = f32::from_bits(x.to_bits() + 1001);
+        }
+        assert!(tan(f32::MAX).is_finite());
+        assert!(tan(f32::MIN).is_finite());
+    }
+
+    #[test]
+    fn tan_edge_cases() {
+        assert!(tan(f32::NAN).is_nan());
+        assert!(tan(f32::INFINITY).is_nan());
+        assert!(tan(f32::NEG_INFINITY).is_nan());
+        assert_eq!(tan(0.0).to_bits(), 0.0f32.to_bits());
+        assert_eq!(tan(-0.0).to_bits(), (-0.0f32).to_bits());
+        // f32 π/2 is just past the pole
+        assert!(tan(FRAC_PI_2) < -1e7);
+        assert!(tan(FRAC_PI_2.prev()) > 1e7);
+        assert!(tan(PI).abs() < 1e-6);
+    }
+
+    #[test]
+    #[cfg(feature = "strict-raw")]
+    fn raw_strict_domain() {
+        ::float::assert_raw_domains(&[
+            (tan_raw, &[-8192.0, 8192.0], &[-1e4, 1e4, f32::NAN]),
+        ]);
+        assert!(tan(f32::NAN).is_nan());
+        assert!(tan(1e10).is_finite());
+    }
+}